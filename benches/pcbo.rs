@@ -1,6 +1,6 @@
 use bitvec::prelude::*;
-use criterion::{Criterion, black_box, criterion_group, criterion_main};
-use fcars::FormalContext;
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use fcars::{Algorithm, FormalContext};
 
 fn patterned_context(objects_len: usize, attributes_len: usize) -> FormalContext<usize, usize> {
     let objects = (0..objects_len).collect::<Vec<_>>();
@@ -49,5 +49,30 @@ fn bench_pcbo_materialize(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_pcbo_count, bench_pcbo_materialize);
+fn bench_algorithms(c: &mut Criterion) {
+    let algorithms = [Algorithm::PCbO, Algorithm::FCbO, Algorithm::InClose];
+    for (name, context) in [
+        ("mask_24x24", patterned_context(24, 24)),
+        ("dense_129x24", patterned_context(129, 24)),
+    ] {
+        // The throughput is the number of closures each algorithm computes, so
+        // criterion reports closures per second next to the time.
+        let mut group = c.benchmark_group(format!("algorithms_count_{name}"));
+        for algorithm in algorithms {
+            let closures = context.enumeration_stats(algorithm).closures;
+            group.throughput(Throughput::Elements(closures as u64));
+            group.bench_function(format!("{algorithm:?}"), |b| {
+                b.iter(|| black_box(&context).num_concepts_with_algorithm(algorithm))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(
+    benches,
+    bench_pcbo_count,
+    bench_pcbo_materialize,
+    bench_algorithms
+);
 criterion_main!(benches);
@@ -0,0 +1,88 @@
+use bitvec::prelude::*;
+use criterion::{BatchSize, Criterion, black_box, criterion_group, criterion_main};
+use fcars::FormalContext;
+
+// A sparse context with many duplicate and reducible rows, like real data.
+fn patterned_context(objects_len: usize, attributes_len: usize) -> FormalContext<usize, usize> {
+    let objects = (0..objects_len).collect::<Vec<_>>();
+    let attributes = (0..attributes_len).collect::<Vec<_>>();
+    let relation = (0..objects_len)
+        .map(|object| {
+            (0..attributes_len)
+                .map(|attribute| {
+                    let x = (object % 97).wrapping_mul(31) ^ attribute.wrapping_mul(17);
+                    x % 11 < 3 || (object + attribute) % 13 == 0
+                })
+                .collect::<BitVec>()
+        })
+        .collect::<Vec<_>>();
+
+    FormalContext::new(objects, attributes, relation)
+}
+
+// The previous reduction, which rescans the whole context after every
+// removal, for comparison. The unit tests check `reduce` against the same
+// algorithm; it is copied here so it stays out of the public API.
+fn reduce_naive(context: &mut FormalContext<usize, usize>) {
+    fn redundant(rows: &[BitVec], width: usize) -> Option<usize> {
+        (0..rows.len()).find(|&i| {
+            let mut meet = BitVec::repeat(true, width);
+            for j in 0..rows.len() {
+                if i != j && rows[i].iter_ones().all(|k| rows[j][k]) {
+                    meet &= &rows[j];
+                }
+            }
+            meet == rows[i]
+        })
+    }
+    loop {
+        let rows = (0..context.objects.len())
+            .map(|i| context.get_object_intent(i).clone())
+            .collect::<Vec<_>>();
+        match redundant(&rows, context.attributes.len()) {
+            Some(i) => context.remove_objects_idx(&[i]),
+            None => break,
+        }
+    }
+    loop {
+        let columns = (0..context.attributes.len())
+            .map(|j| context.get_attribute_extent(j).clone())
+            .collect::<Vec<_>>();
+        match redundant(&columns, context.objects.len()) {
+            Some(j) => context.remove_attributes_idx(&[j]),
+            None => break,
+        }
+    }
+}
+
+fn bench_reduce(c: &mut Criterion) {
+    let small_context = patterned_context(300, 40);
+    let large_context = patterned_context(20_000, 64);
+
+    c.bench_function("reduce_300x40", |b| {
+        b.iter_batched(
+            || small_context.clone(),
+            |mut context| black_box(&mut context).reduce(),
+            BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("reduce_naive_300x40", |b| {
+        b.iter_batched(
+            || small_context.clone(),
+            |mut context| reduce_naive(black_box(&mut context)),
+            BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("reduce_20000x64", |b| {
+        b.iter_batched(
+            || large_context.clone(),
+            |mut context| black_box(&mut context).reduce(),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, bench_reduce);
+criterion_main!(benches);
@@ -0,0 +1,511 @@
+use crate::FormalContext;
+use crate::RawFormalConcept;
+use crate::bit_fiddling::*;
+use crate::pcbo::{CbOSearch, count_all};
+
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Alternatives to the PCbO search of `pcbo.rs`. All three walk the same
+// Close-by-One tree: a concept `(A, B)` reached by adding attribute `j - 1`
+// has children `((A ∩ j')'', ...)` for attributes `j' >= j` not in `B`,
+// accepted when the closure adds no attribute before `j'`. They differ in
+// how much work a rejected child costs.
+//
+// - PCbO computes the closure of every candidate and then tests it. It is
+//   always run, and measured, by the search of `pcbo.rs`; see `Counted`.
+// - FCbO (Outrata & Vychodil, 2012) remembers, for every attribute, the
+//   intent of the last closure that failed the test. A descendant whose
+//   intent does not contain that failed intent's attributes before `j'`
+//   would fail again, so it is skipped without computing anything. The
+//   failed intents are passed down the tree.
+// - In-Close (Andrews, 2011, "In-Close2") never computes closures. While
+//   scanning candidates, an attribute whose extent contains `A` is added
+//   to `B` directly, and the intent of a child is completed the same way
+//   when the child is visited. Canonicity is tested by checking that no
+//   earlier attribute outside `B` contains the candidate extent.
+//
+// The FCbO and In-Close search is parallelised over the children of the
+// maximal concept.
+
+/// A concept enumeration algorithm.
+///
+/// All algorithms find the same concepts, but differ in speed depending on
+/// the shape of the context; see [`FormalContext::enumeration_stats`] to
+/// compare how much work they do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Algorithm {
+    /// Parallel Close-by-One, which closes every candidate concept and then
+    /// tests whether it is generated for the first time.
+    #[default]
+    PCbO,
+    /// Fast Close-by-One, which passes the closures that failed the
+    /// canonicity test down the search tree and skips candidates that are
+    /// known to fail again.
+    FCbO,
+    /// In-Close, which computes intents incrementally and tests canonicity
+    /// on extents, without computing closures.
+    InClose,
+}
+
+/// How much work a concept enumeration did; see
+/// [`FormalContext::enumeration_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EnumerationStats {
+    /// The number of concepts found.
+    pub concepts: usize,
+    /// The number of candidate children whose extent was computed.
+    pub candidates: usize,
+    /// The number of closures (intents of candidate extents) computed. This
+    /// is zero for [`Algorithm::InClose`].
+    pub closures: usize,
+}
+
+impl EnumerationStats {
+    fn merge(mut self, other: Self) -> Self {
+        self.concepts += other.concepts;
+        self.candidates += other.candidates;
+        self.closures += other.closures;
+        self
+    }
+}
+
+impl<A: Sync, B: Sync> FormalContext<A, B> {
+    /// Enumerates all raw formal concepts in this context with the given
+    /// algorithm.
+    ///
+    /// [`Algorithm::PCbO`] is the same as [`FormalContext::all_concepts_raw`].
+    /// Concept order is an implementation detail and should not be relied on.
+    pub fn all_concepts_raw_with_algorithm(&self, algorithm: Algorithm) -> Vec<RawFormalConcept> {
+        if algorithm == Algorithm::PCbO {
+            return self.all_concepts_raw();
+        }
+        match self.mask_context() {
+            Some(context) => search(&context, algorithm, true).0,
+            None => search(&self.dense_context(), algorithm, true).0,
+        }
+    }
+
+    /// Counts the formal concepts in this context with the given algorithm.
+    ///
+    /// [`Algorithm::PCbO`] is the same as [`FormalContext::num_concepts`].
+    pub fn num_concepts_with_algorithm(&self, algorithm: Algorithm) -> usize {
+        if algorithm == Algorithm::PCbO {
+            return self.num_concepts();
+        }
+        self.enumeration_stats(algorithm).concepts
+    }
+
+    /// Counts the formal concepts in this context with the given algorithm,
+    /// along with the candidate concepts and closures it computed on the
+    /// way.
+    ///
+    /// The numbers for [`Algorithm::PCbO`] are measured on the same search
+    /// as [`FormalContext::num_concepts`].
+    pub fn enumeration_stats(&self, algorithm: Algorithm) -> EnumerationStats {
+        if algorithm == Algorithm::PCbO {
+            return match self.mask_context() {
+                Some(context) => Counted::stats(&context),
+                None => Counted::stats(&self.dense_context()),
+            };
+        }
+        match self.mask_context() {
+            Some(context) => search(&context, algorithm, false).1,
+            None => search(&self.dense_context(), algorithm, false).1,
+        }
+    }
+}
+
+// Runs the PCbO search of `pcbo.rs` while counting the candidates it tries.
+// Without a minimum support, every candidate costs one extent and one
+// closure.
+struct Counted<'a, S> {
+    search: &'a S,
+    candidates: AtomicUsize,
+}
+
+impl<'a, S: CbOSearch> Counted<'a, S> {
+    fn stats(search: &'a S) -> EnumerationStats {
+        let counted = Self {
+            search,
+            candidates: AtomicUsize::new(0),
+        };
+        let concepts = count_all(&counted);
+        let candidates = counted.candidates.into_inner();
+        EnumerationStats {
+            concepts,
+            candidates,
+            closures: candidates,
+        }
+    }
+}
+
+impl<S: CbOSearch> CbOSearch for Counted<'_, S> {
+    type Node = S::Node;
+
+    fn elements_len(&self) -> usize {
+        self.search.elements_len()
+    }
+
+    fn root(&self) -> S::Node {
+        self.search.root()
+    }
+
+    fn node_contains(&self, node: &S::Node, element: usize) -> bool {
+        self.search.node_contains(node, element)
+    }
+
+    fn canonical_child(&self, node: &S::Node, element: usize) -> Option<S::Node> {
+        self.candidates.fetch_add(1, Ordering::Relaxed);
+        self.search.canonical_child(node, element)
+    }
+}
+
+// The operations the FCbO and In-Close search needs from a bitset engine.
+trait Engine: Sync {
+    type Concept: Send;
+
+    fn root(&self) -> Self::Concept;
+
+    fn to_raw(&self, concept: &Self::Concept) -> RawFormalConcept;
+
+    // The children of `concept` in the search tree of `algorithm`, each with
+    // its first candidate attribute. In-Close completes the intent of
+    // `concept` in the process. `failed` holds the failed FCbO closures
+    // inherited by `concept` (empty if there are none yet), and is updated
+    // to those its children inherit.
+    fn children(
+        &self,
+        algorithm: Algorithm,
+        concept: &mut Self::Concept,
+        y: usize,
+        failed: &mut Vec<u128>,
+        stats: &mut EnumerationStats,
+    ) -> Vec<(Self::Concept, usize)>;
+}
+
+// Walk the whole search tree, collecting the concepts if `collect` holds.
+fn search<E: Engine>(
+    engine: &E,
+    algorithm: Algorithm,
+    collect: bool,
+) -> (Vec<RawFormalConcept>, EnumerationStats) {
+    let mut stats = EnumerationStats::default();
+    let mut failed = Vec::new();
+    let mut root = engine.root();
+    let children = engine.children(algorithm, &mut root, 0, &mut failed, &mut stats);
+    let mut concepts = Vec::new();
+    visit(engine, root, collect, &mut concepts, &mut stats);
+    children
+        .into_par_iter()
+        .map(|(concept, y)| {
+            let mut concepts = Vec::new();
+            let mut stats = EnumerationStats::default();
+            subtree(
+                engine,
+                algorithm,
+                concept,
+                y,
+                &failed,
+                collect,
+                &mut concepts,
+                &mut stats,
+            );
+            (concepts, stats)
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .fold(
+            (concepts, stats),
+            |(mut concepts, stats), (more, more_stats)| {
+                concepts.extend(more);
+                (concepts, stats.merge(more_stats))
+            },
+        )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn subtree<E: Engine>(
+    engine: &E,
+    algorithm: Algorithm,
+    mut concept: E::Concept,
+    y: usize,
+    failed: &[u128],
+    collect: bool,
+    concepts: &mut Vec<RawFormalConcept>,
+    stats: &mut EnumerationStats,
+) {
+    let mut failed = failed.to_vec();
+    let children = engine.children(algorithm, &mut concept, y, &mut failed, stats);
+    visit(engine, concept, collect, concepts, stats);
+    for (child, y) in children {
+        subtree(
+            engine, algorithm, child, y, &failed, collect, concepts, stats,
+        );
+    }
+}
+
+fn visit<E: Engine>(
+    engine: &E,
+    concept: E::Concept,
+    collect: bool,
+    concepts: &mut Vec<RawFormalConcept>,
+    stats: &mut EnumerationStats,
+) {
+    stats.concepts += 1;
+    if collect {
+        concepts.push(engine.to_raw(&concept));
+    }
+}
+
+impl Engine for MaskContext {
+    type Concept = MaskConcept;
+
+    fn root(&self) -> MaskConcept {
+        self.max_concept()
+    }
+
+    fn to_raw(&self, concept: &MaskConcept) -> RawFormalConcept {
+        self.to_raw_concept(*concept)
+    }
+
+    fn children(
+        &self,
+        algorithm: Algorithm,
+        concept: &mut MaskConcept,
+        y: usize,
+        failed: &mut Vec<u128>,
+        stats: &mut EnumerationStats,
+    ) -> Vec<(MaskConcept, usize)> {
+        let mut children = Vec::new();
+        for j in y..self.attributes_len() {
+            if self.concept_has_attribute(*concept, j) {
+                continue;
+            }
+            let before_j = low_bits(j);
+            if algorithm == Algorithm::FCbO
+                && failed
+                    .get(j)
+                    .is_some_and(|&intent| intent & before_j & !concept.intent != 0)
+            {
+                continue;
+            }
+            stats.candidates += 1;
+            let extent = concept.extent & self.attribute_extent(j);
+            if algorithm == Algorithm::InClose {
+                if extent == concept.extent {
+                    concept.intent |= 1 << j;
+                } else if self.in_close_canonical(extent, concept.intent, j) {
+                    // The intent is completed below, once every attribute
+                    // of the parent is known.
+                    children.push((MaskConcept { extent, intent: 0 }, j));
+                }
+                continue;
+            }
+            stats.closures += 1;
+            let intent = self.induce_r(extent);
+            if (concept.intent ^ intent) & before_j == 0 {
+                children.push((MaskConcept { extent, intent }, j + 1));
+            } else if algorithm == Algorithm::FCbO {
+                if failed.is_empty() {
+                    failed.resize(self.attributes_len(), 0);
+                }
+                failed[j] = intent;
+            }
+        }
+        if algorithm == Algorithm::InClose {
+            for (child, j) in &mut children {
+                child.intent = concept.intent | 1 << *j;
+                *j += 1;
+            }
+        }
+        children
+    }
+}
+
+impl MaskContext {
+    // Whether no attribute before `j` outside `intent` has every object of
+    // `extent`.
+    fn in_close_canonical(&self, extent: u128, intent: u128, j: usize) -> bool {
+        let mut attributes = low_bits(j) & !intent;
+        while attributes != 0 {
+            let attribute = attributes.trailing_zeros() as usize;
+            if extent & !self.attribute_extent(attribute) == 0 {
+                return false;
+            }
+            attributes &= attributes - 1;
+        }
+        true
+    }
+}
+
+impl Engine for DenseContext {
+    type Concept = DenseConcept;
+
+    fn root(&self) -> DenseConcept {
+        self.max_concept()
+    }
+
+    fn to_raw(&self, concept: &DenseConcept) -> RawFormalConcept {
+        self.to_raw_concept(concept)
+    }
+
+    fn children(
+        &self,
+        algorithm: Algorithm,
+        concept: &mut DenseConcept,
+        y: usize,
+        failed: &mut Vec<u128>,
+        stats: &mut EnumerationStats,
+    ) -> Vec<(DenseConcept, usize)> {
+        let words = self.attr_words();
+        let mut children = Vec::new();
+        for j in y..self.attributes_len() {
+            if self.concept_has_attribute(concept, j) {
+                continue;
+            }
+            // `failed` is flat, with one intent of `words` words per
+            // attribute.
+            if algorithm == Algorithm::FCbO
+                && !failed.is_empty()
+                && !dense_prefix_subset(&failed[j * words..][..words], &concept.intent, j)
+            {
+                continue;
+            }
+            stats.candidates += 1;
+            let extent: Vec<u128> = concept
+                .extent
+                .iter()
+                .zip(self.attribute_extent(j))
+                .map(|(left, right)| left & right)
+                .collect();
+            if algorithm == Algorithm::InClose {
+                if extent == concept.extent {
+                    concept.intent[j / u128::BITS as usize] |= 1 << (j % u128::BITS as usize);
+                } else if self.in_close_canonical(&extent, concept, j) {
+                    let intent = Vec::new();
+                    children.push((DenseConcept { extent, intent }, j));
+                }
+                continue;
+            }
+            stats.closures += 1;
+            let mut intent = vec![0; words];
+            self.induce_r_into(&extent, &mut intent);
+            if dense_prefix_eq(&concept.intent, &intent, j) {
+                children.push((DenseConcept { extent, intent }, j + 1));
+            } else if algorithm == Algorithm::FCbO {
+                if failed.is_empty() {
+                    failed.resize(self.attributes_len() * words, 0);
+                }
+                failed[j * words..][..words].copy_from_slice(&intent);
+            }
+        }
+        if algorithm == Algorithm::InClose {
+            for (child, j) in &mut children {
+                child.intent = concept.intent.clone();
+                child.intent[*j / u128::BITS as usize] |= 1 << (*j % u128::BITS as usize);
+                *j += 1;
+            }
+        }
+        children
+    }
+}
+
+impl DenseContext {
+    // Whether no attribute before `j` outside the intent of `concept` has
+    // every object of `extent`.
+    fn in_close_canonical(&self, extent: &[u128], concept: &DenseConcept, j: usize) -> bool {
+        (0..j).all(|attribute| {
+            self.concept_has_attribute(concept, attribute)
+                || !dense_is_subset(extent, self.attribute_extent(attribute))
+        })
+    }
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitvec::prelude::*;
+
+    fn lcg_context(objects: usize, attributes: usize, seed: u64) -> FormalContext<usize, usize> {
+        let mut state = seed;
+        let relation = (0..objects)
+            .map(|_| {
+                (0..attributes)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        (state >> 33) % 5 < 2
+                    })
+                    .collect::<BitVec>()
+            })
+            .collect();
+        FormalContext::new((0..objects).collect(), (0..attributes).collect(), relation)
+    }
+
+    fn sorted_extents(mut concepts: Vec<RawFormalConcept>) -> Vec<BitVec> {
+        concepts.sort_by(|a, b| a.extent.cmp(&b.extent));
+        concepts.into_iter().map(|concept| concept.extent).collect()
+    }
+
+    #[test]
+    fn test_algorithms_agree() {
+        // The first two contexts use the `u128` engine, the last two the
+        // dense one.
+        for (objects, attributes, seed) in [(20, 12, 1), (40, 16, 2), (150, 14, 3), (12, 130, 4)] {
+            let context = lcg_context(objects, attributes, seed);
+            let expected = context.all_concepts_raw();
+
+            for algorithm in [Algorithm::PCbO, Algorithm::FCbO, Algorithm::InClose] {
+                let concepts = context.all_concepts_raw_with_algorithm(algorithm);
+                for concept in &concepts {
+                    assert_eq!(concept.intent, context.induce_r(&concept.extent));
+                    assert_eq!(concept.extent, context.induce_l(&concept.intent));
+                }
+                assert_eq!(
+                    sorted_extents(concepts),
+                    sorted_extents(expected.clone()),
+                    "{algorithm:?} on {objects}x{attributes}"
+                );
+                assert_eq!(
+                    context.num_concepts_with_algorithm(algorithm),
+                    expected.len()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_enumeration_stats() {
+        for context in [lcg_context(40, 16, 5), lcg_context(150, 14, 6)] {
+            let pcbo = context.enumeration_stats(Algorithm::PCbO);
+            let fcbo = context.enumeration_stats(Algorithm::FCbO);
+            let in_close = context.enumeration_stats(Algorithm::InClose);
+
+            assert_eq!(pcbo.concepts, context.num_concepts());
+            assert_eq!(fcbo.concepts, pcbo.concepts);
+            assert_eq!(in_close.concepts, pcbo.concepts);
+            assert_eq!(pcbo.closures, pcbo.candidates);
+            assert!(fcbo.closures < pcbo.closures);
+            assert_eq!(in_close.closures, 0);
+        }
+    }
+
+    #[test]
+    fn test_pcbo_stats_count_every_candidate() {
+        // Every candidate in the Boolean lattice on three atoms is canonical,
+        // so PCbO tries one per concept but the maximal one.
+        let context = FormalContext::contranomial_scale(vec![0, 1, 2]);
+
+        assert_eq!(
+            context.enumeration_stats(Algorithm::PCbO),
+            EnumerationStats {
+                concepts: 8,
+                candidates: 7,
+                closures: 7,
+            }
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use crate::FormalContext;
+use crate::bit_fiddling::*;
+
+use bitvec::prelude::*;
+use std::fmt::Display;
+
+/// Helper for printing a context together with its arrow relations; see
+/// [`FormalContext::display_arrows`].
+pub struct ArrowDisplay<'a, A, B> {
+    context: &'a FormalContext<A, B>,
+    up: Vec<BitVec>,
+    down: Vec<BitVec>,
+}
+
+impl<A, B> FormalContext<A, B> {
+    /// Computes Wille's down-arrow relation `↙`.
+    ///
+    /// Object `g` has a down arrow to attribute `m` when `g` does not have `m`,
+    /// but every object whose intent strictly contains the intent of `g` does.
+    /// The result has the same shape as the relation: `result[i][j]` is true
+    /// when object `i` has a down arrow to attribute `j`.
+    pub fn down_arrows(&self) -> Vec<BitVec> {
+        let rows: Vec<BitVec> = (0..self.objects.len())
+            .map(|i| self.get_object_intent(i).clone())
+            .collect();
+        strict_superset_gaps(&rows, self.attributes.len())
+    }
+
+    /// Computes Wille's up-arrow relation `↗`.
+    ///
+    /// Object `g` has an up arrow to attribute `m` when `g` does not have `m`,
+    /// but `g` has every attribute whose extent strictly contains the extent
+    /// of `m`. The result has the same shape as the relation: `result[i][j]` is
+    /// true when object `i` has an up arrow to attribute `j`.
+    pub fn up_arrows(&self) -> Vec<BitVec> {
+        let columns: Vec<BitVec> = (0..self.attributes.len())
+            .map(|j| self.get_attribute_extent(j).clone())
+            .collect();
+        transpose(
+            &strict_superset_gaps(&columns, self.objects.len()),
+            self.objects.len(),
+        )
+    }
+
+    /// Computes the double-arrow relation `↕`, the intersection of the up-
+    /// and down-arrow relations.
+    ///
+    /// In a clarified context, an object is irreducible exactly when its row
+    /// has a down arrow, and an attribute exactly when its column has an up
+    /// arrow. Double arrows additionally describe how irreducible objects and
+    /// attributes pair up, which is used e.g. to test lattices for
+    /// distributivity.
+    pub fn double_arrows(&self) -> Vec<BitVec> {
+        self.up_arrows()
+            .into_iter()
+            .zip(self.down_arrows())
+            .map(|(up, down)| up & down)
+            .collect()
+    }
+
+    /// Returns a value that displays this context like its [`Display`]
+    /// implementation, but marks the non-incidences that carry arrows with
+    /// `↗`, `↙` or `↕`.
+    pub fn display_arrows(&self) -> ArrowDisplay<'_, A, B> {
+        ArrowDisplay {
+            context: self,
+            up: self.up_arrows(),
+            down: self.down_arrows(),
+        }
+    }
+}
+
+impl<A: Display, B: Display> Display for ArrowDisplay<'_, A, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Print header
+        write!(f, "{:>10}", "")?;
+        for attr in &self.context.attributes {
+            write!(f, "{:>5}", attr)?;
+        }
+        writeln!(f)?;
+        // Print each row
+        for (i, obj) in self.context.objects.iter().enumerate() {
+            write!(f, "{:>10}", obj)?;
+            for j in 0..self.context.attributes.len() {
+                let mark = match (
+                    self.context.get_relation_idx(i, j),
+                    self.up[i][j],
+                    self.down[i][j],
+                ) {
+                    (true, _, _) => "1",
+                    (false, true, true) => "↕",
+                    (false, true, false) => "↗",
+                    (false, false, true) => "↙",
+                    (false, false, false) => "0",
+                };
+                write!(f, "{:>5}", mark)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> FormalContext<&'static str, &'static str> {
+        FormalContext::new(
+            vec!["a", "b", "c"],
+            vec!["1", "2", "3"],
+            vec![
+                bitvec![1, 0, 1], // a
+                bitvec![1, 1, 1], // b
+                bitvec![0, 1, 1], // c
+            ],
+        )
+    }
+
+    #[test]
+    fn test_arrow_relations() {
+        let context = example();
+
+        assert_eq!(
+            context.down_arrows(),
+            vec![bitvec![0, 1, 0], bitvec![0, 0, 0], bitvec![1, 0, 0]]
+        );
+        assert_eq!(
+            context.up_arrows(),
+            vec![bitvec![0, 1, 0], bitvec![0, 0, 0], bitvec![1, 0, 0]]
+        );
+        assert_eq!(context.double_arrows(), context.down_arrows());
+    }
+
+    #[test]
+    fn test_contranomial_scale_double_arrows() {
+        let context = FormalContext::contranomial_scale((0..5).collect::<Vec<_>>());
+
+        let double = context.double_arrows();
+
+        for (i, row) in double.iter().enumerate() {
+            assert_eq!(row.iter_ones().collect::<Vec<_>>(), vec![i]);
+        }
+    }
+
+    #[test]
+    fn test_display_arrows() {
+        let context = FormalContext::new(
+            vec!["a", "b"],
+            vec!["1", "2", "3"],
+            vec![bitvec![1, 0, 0], bitvec![1, 1, 0]],
+        );
+
+        let expected = concat!(
+            "              1    2    3\n",
+            "         a    1    ↕    0\n",
+            "         b    1    1    ↕\n",
+        );
+        assert_eq!(context.display_arrows().to_string(), expected);
+    }
+}
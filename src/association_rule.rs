@@ -0,0 +1,244 @@
+use crate::FormalContext;
+use crate::concept_lattice::{sort_concepts, upper_neighbours};
+
+use bitvec::prelude::*;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// A partial implication (association rule) `premise => conclusion`.
+///
+/// Both sides are bitsets over the attribute indices of some
+/// [`FormalContext`]. Unlike an [`Implication`](crate::Implication), the rule
+/// need not hold for every object; the statistics describe how often it does.
+///
+/// All fields are exported for ease of use.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssociationRule {
+    /// `premise[j]` is `true` if and only if the j-th attribute is in the premise.
+    pub premise: BitVec,
+    /// `conclusion[j]` is `true` if and only if the j-th attribute is in the conclusion.
+    pub conclusion: BitVec,
+    /// The number of objects having every attribute of the premise and the
+    /// conclusion.
+    pub support: usize,
+    /// The fraction of objects having the premise that also have the
+    /// conclusion.
+    pub confidence: f64,
+    /// The confidence divided by the fraction of all objects that have the
+    /// conclusion. Values above 1 indicate a positive correlation.
+    pub lift: f64,
+}
+
+/// Helper for printing an [`AssociationRule`] with labels; see
+/// [`AssociationRule::display`].
+pub struct AssociationRuleDisplay<'a, A, B> {
+    rule: &'a AssociationRule,
+    context: &'a FormalContext<A, B>,
+}
+
+impl AssociationRule {
+    /// Iterates over the labels of the premise attributes in `context`.
+    pub fn premise_names_iter<'a, A, B>(
+        &'a self,
+        context: &'a FormalContext<A, B>,
+    ) -> impl Iterator<Item = &'a B> {
+        self.premise.iter_ones().map(|j| &context.attributes[j])
+    }
+
+    /// Iterates over the labels of the conclusion attributes in `context`.
+    pub fn conclusion_names_iter<'a, A, B>(
+        &'a self,
+        context: &'a FormalContext<A, B>,
+    ) -> impl Iterator<Item = &'a B> {
+        self.conclusion.iter_ones().map(|j| &context.attributes[j])
+    }
+
+    /// Returns a value that displays this rule with the attribute labels of
+    /// `context`, e.g. `["a"] => ["b"] (support: 2, confidence: 0.667, lift: 0.889)`.
+    pub fn display<'a, A, B>(
+        &'a self,
+        context: &'a FormalContext<A, B>,
+    ) -> AssociationRuleDisplay<'a, A, B> {
+        AssociationRuleDisplay {
+            rule: self,
+            context,
+        }
+    }
+}
+
+impl<A, B: Debug> std::fmt::Display for AssociationRuleDisplay<'_, A, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let premise: Vec<_> = self.rule.premise_names_iter(self.context).collect();
+        let conclusion: Vec<_> = self.rule.conclusion_names_iter(self.context).collect();
+        write!(
+            f,
+            "{:?} => {:?} (support: {}, confidence: {:.3}, lift: {:.3})",
+            premise, conclusion, self.rule.support, self.rule.confidence, self.rule.lift
+        )
+    }
+}
+
+impl<A: Sync, B: Sync> FormalContext<A, B> {
+    /// Computes the Luxenburger basis of the association rules of this context
+    /// with support at least `min_support` and confidence at least
+    /// `min_confidence`.
+    ///
+    /// There is one rule for every pair of neighbouring frequent concepts
+    /// `C < D`: its premise is the intent of `D`, and its conclusion consists of
+    /// the attributes that the intent of `C` adds. Its support is the extent
+    /// size of `C`, and its confidence is `|extent(C)| / |extent(D)|`, which is
+    /// always below 1. Together with the [canonical
+    /// basis](FormalContext::canonical_basis) for the exact rules, these rules
+    /// determine the support and confidence of every association rule between
+    /// frequent itemsets.
+    ///
+    /// Frequent concepts are enumerated with
+    /// [`FormalContext::all_concepts_raw_with_min_support`], and neighbours are
+    /// computed directly rather than by comparing pairs of concepts. Rules are
+    /// ordered by decreasing support of their lower concept. Rules hold for at
+    /// least one object, so a `min_support` of 0 is treated as 1: a lower
+    /// concept with an empty extent would give a rule that never applies.
+    pub fn luxenburger_basis(
+        &self,
+        min_support: usize,
+        min_confidence: f64,
+    ) -> Vec<AssociationRule> {
+        let mut concepts = self.all_concepts_raw_with_min_support(min_support.max(1));
+        sort_concepts(&mut concepts);
+        let intent_index: HashMap<BitVec, usize> = concepts
+            .iter()
+            .enumerate()
+            .map(|(i, concept)| (concept.intent.clone(), i))
+            .collect();
+        let concepts = &concepts;
+
+        concepts
+            .par_iter()
+            .flat_map_iter(|lower| {
+                let support = lower.extent.count_ones();
+                upper_neighbours(self, lower, &intent_index)
+                    .into_iter()
+                    .filter_map(move |i| {
+                        let upper = &concepts[i];
+                        let upper_support = upper.extent.count_ones();
+                        let conclusion = lower.intent.clone() & !upper.intent.clone();
+                        let conclusion_support = self.induce_l(&conclusion).count_ones();
+                        // Both extents contain the nonempty lower extent, but
+                        // never divide by zero.
+                        if upper_support * conclusion_support == 0 {
+                            return None;
+                        }
+                        let confidence = support as f64 / upper_support as f64;
+                        if confidence < min_confidence {
+                            return None;
+                        }
+                        Some(AssociationRule {
+                            premise: upper.intent.clone(),
+                            conclusion,
+                            support,
+                            confidence,
+                            lift: (support * self.objects.len()) as f64
+                                / (upper_support * conclusion_support) as f64,
+                        })
+                    })
+            })
+            .collect()
+    }
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Premise labels, conclusion labels, support, confidence and lift.
+    type RuleSummary = (Vec<usize>, Vec<usize>, usize, f64, f64);
+
+    fn summary(
+        context: &FormalContext<String, usize>,
+        rules: &[AssociationRule],
+    ) -> Vec<RuleSummary> {
+        let mut summary: Vec<_> = rules
+            .iter()
+            .map(|rule| {
+                (
+                    rule.premise_names_iter(context).copied().collect(),
+                    rule.conclusion_names_iter(context).copied().collect(),
+                    rule.support,
+                    rule.confidence,
+                    rule.lift,
+                )
+            })
+            .collect();
+        summary.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        summary
+    }
+
+    #[test]
+    fn test_luxenburger_basis() {
+        let context = FormalContext::from_dat("1 2 3\n1 2\n1\n2 3\n".as_bytes());
+
+        let rules = context.luxenburger_basis(0, 0.0);
+
+        assert_eq!(
+            summary(&context, &rules),
+            vec![
+                (vec![], vec![1], 3, 0.75, 1.0),
+                (vec![], vec![2], 3, 0.75, 1.0),
+                (vec![1], vec![2], 2, 2.0 / 3.0, 8.0 / 9.0),
+                (vec![1, 2], vec![3], 1, 0.5, 1.0),
+                (vec![2], vec![1], 2, 2.0 / 3.0, 8.0 / 9.0),
+                (vec![2], vec![3], 2, 2.0 / 3.0, 4.0 / 3.0),
+                (vec![2, 3], vec![1], 1, 0.5, 2.0 / 3.0),
+            ]
+        );
+        assert_eq!(
+            rules[0].display(&context).to_string(),
+            "[] => [2] (support: 3, confidence: 0.750, lift: 1.000)"
+        );
+    }
+
+    #[test]
+    fn test_luxenburger_basis_thresholds() {
+        let context = FormalContext::from_dat("1 2 3\n1 2\n1\n2 3\n".as_bytes());
+
+        assert_eq!(context.luxenburger_basis(2, 0.0).len(), 5);
+        assert_eq!(
+            summary(&context, &context.luxenburger_basis(2, 0.7)),
+            vec![
+                (vec![], vec![1], 3, 0.75, 1.0),
+                (vec![], vec![2], 3, 0.75, 1.0),
+            ]
+        );
+        assert!(context.luxenburger_basis(5, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_luxenburger_basis_skips_empty_extents() {
+        // The bottom concept of the identity context has an empty extent.
+        let context = FormalContext::from_dat(
+            "1
+2
+3
+"
+            .as_bytes(),
+        );
+
+        let rules = context.luxenburger_basis(0, 0.0);
+
+        assert_eq!(
+            summary(&context, &rules),
+            vec![
+                (vec![], vec![1], 1, 1.0 / 3.0, 1.0),
+                (vec![], vec![2], 1, 1.0 / 3.0, 1.0),
+                (vec![], vec![3], 1, 1.0 / 3.0, 1.0),
+            ]
+        );
+        assert!(
+            rules
+                .iter()
+                .all(|rule| rule.support > 0 && !rule.lift.is_nan())
+        );
+    }
+}
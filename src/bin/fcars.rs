@@ -4,34 +4,82 @@ use std::fs::File;
 use std::io::{self, Read, Write};
 use std::process::ExitCode;
 
-use fcars::FormalContext;
+use bitvec::prelude::*;
+
+use fcars::{
+    CsvOptions, Expert, ExpertAnswer, ExplorationError, FormalConcept, FormalContext, Implication,
+    LatticeLabels, ParseError, StabilityOptions,
+};
 
 const USAGE: &str = "\
-Usage: fcars [-n] [-o file] [--dat | --cxt] [file_in]
+Usage: fcars [-n] [-V] [-o file] [--min-support s] [--sort-by m [--top k]] [--format f] [--labels l] [--dat | --cxt | --csv | --cex] [file_in]
+       fcars explore [-o file] [--dat | --cxt | --csv | --cex] file_in
 
 Options:
   -n                By default, all concepts are printed (one per line). If this flag is given, only the number of concepts is printed.
   -V                Verbose output: print the context, whether it is reduced, and the number of concepts.
   -o file           Write output to file instead of stdout.
-  [--dat | --cxt]   Specifies input format. By default, .dat format is assumed. If more than one format flag is specified, the last one takes precedence.
+  --min-support s   Only consider concepts whose extent has at least s objects. If s is a decimal fraction between 0 and 1 (e.g. 0.25), it is taken relative to the number of objects.
+  --sort-by m       Print concepts ranked by the measure m, each preceded by its value: stability (intensional stability), extensional-stability, separation, support, or probability (the probability index, where small values rank first). Stability is estimated from random samples for extents or intents of more than 20 elements.
+  --top k           With --sort-by, print only the k highest-ranked concepts.
+  --format f        Output format. concepts (the default) prints one concept per line; dot prints the Hasse diagram of the concept lattice in Graphviz DOT format; svg draws the line diagram of the concept lattice as an SVG image; json prints the concepts as a JSON array (requires the serde feature).
+  --labels l        Node labels for diagram formats. reduced (the default) shows each object and attribute once; full shows every extent and intent; sizes shows extent and intent sizes.
+  [--dat | --cxt | --csv | --cex]
+                    Specifies input format. By default, the format is detected from the extension of file_in (.dat, .cxt, .csv or .cex), and .dat format is assumed otherwise. --csv reads a comma-separated cross table with attribute names in the first row and object names in the first column; --cex reads ConExp XML files. If more than one format flag is specified, the last one takes precedence.
   -h, --help        Print this help message. Disregard all other options and arguments.
 
 Arguments:
     file_in         Path to input file. If not specified, input is read from stdin.
+
+Attribute exploration:
+  fcars explore asks, for each implication that holds in the context of file_in, whether it holds in general.
+  Answer y to accept it, or n to enter a counterexample object and its attributes (separated by commas).
+  The accepted implications are printed at the end. If -o is given, the context extended by the
+  counterexamples is written to that file, in the input format.
 ";
 
 #[derive(Clone, Copy)]
 enum InputFormat {
     Dat,
     Cxt,
+    Csv,
+    Cex,
 }
 
 struct Config {
     count_only: bool,
     verbose: bool,
     output_path: Option<String>,
-    input_format: InputFormat,
+    input_format: Option<InputFormat>,
     input_path: Option<String>,
+    min_support: Option<MinSupport>,
+    sort_by: Option<SortBy>,
+    top: Option<usize>,
+    output_format: OutputFormat,
+    labels: LatticeLabels,
+}
+
+#[derive(Clone, Copy)]
+enum OutputFormat {
+    Concepts,
+    Dot,
+    Svg,
+    Json,
+}
+
+#[derive(Clone, Copy)]
+enum SortBy {
+    Stability,
+    ExtensionalStability,
+    Separation,
+    Support,
+    Probability,
+}
+
+#[derive(Clone, Copy)]
+enum MinSupport {
+    Absolute(usize),
+    Relative(f64),
 }
 
 fn main() -> ExitCode {
@@ -46,7 +94,13 @@ fn main() -> ExitCode {
 }
 
 fn run() -> io::Result<()> {
-    let Some(config) = parse_args(env::args().skip(1))? else {
+    let mut args = env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("explore") {
+        args.next();
+        return explore(args);
+    }
+
+    let Some(config) = parse_args(args)? else {
         print!("{USAGE}");
         return Ok(());
     };
@@ -54,14 +108,27 @@ fn run() -> io::Result<()> {
     let input = open_input(config.input_path.as_deref())?;
     let mut output = open_output(config.output_path.as_deref())?;
 
-    match config.input_format {
+    let input_name = config.input_path.as_deref().unwrap_or("<stdin>");
+    match detect_format(config.input_format, config.input_path.as_deref()) {
         InputFormat::Dat => {
-            let context = FormalContext::from_dat(input);
-            write_result(context, config.count_only, config.verbose, &mut output)
+            let context =
+                FormalContext::try_from_dat(input).map_err(|err| parse_error(input_name, err))?;
+            write_result(context, &config, &mut output)
         }
         InputFormat::Cxt => {
-            let context = FormalContext::from_cxt(input);
-            write_result(context, config.count_only, config.verbose, &mut output)
+            let context =
+                FormalContext::try_from_cxt(input).map_err(|err| parse_error(input_name, err))?;
+            write_result(context, &config, &mut output)
+        }
+        InputFormat::Csv => {
+            let context = FormalContext::try_from_csv(input, &CsvOptions::default())
+                .map_err(|err| parse_error(input_name, err))?;
+            write_result(context, &config, &mut output)
+        }
+        InputFormat::Cex => {
+            let context =
+                FormalContext::try_from_cex(input).map_err(|err| parse_error(input_name, err))?;
+            write_result(context, &config, &mut output)
         }
     }
 }
@@ -70,8 +137,13 @@ fn parse_args(args: impl IntoIterator<Item = String>) -> io::Result<Option<Confi
     let mut count_only = false;
     let mut verbose = false;
     let mut output_path = None;
-    let mut input_format = InputFormat::Dat;
+    let mut input_format = None;
     let mut input_path = None;
+    let mut min_support = None;
+    let mut sort_by = None;
+    let mut top = None;
+    let mut output_format = OutputFormat::Concepts;
+    let mut labels = LatticeLabels::Reduced;
 
     let mut args = args.into_iter();
     while let Some(arg) = args.next() {
@@ -87,8 +159,61 @@ fn parse_args(args: impl IntoIterator<Item = String>) -> io::Result<Option<Confi
                     return Err(invalid_input("-o may only be specified once"));
                 }
             }
-            "--dat" => input_format = InputFormat::Dat,
-            "--cxt" => input_format = InputFormat::Cxt,
+            "--min-support" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| invalid_input("--min-support requires a value"))?;
+                min_support = Some(parse_min_support(&value)?);
+            }
+            "--sort-by" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| invalid_input("--sort-by requires a measure"))?;
+                sort_by = Some(match value.as_str() {
+                    "stability" => SortBy::Stability,
+                    "extensional-stability" => SortBy::ExtensionalStability,
+                    "separation" => SortBy::Separation,
+                    "support" => SortBy::Support,
+                    "probability" => SortBy::Probability,
+                    _ => return Err(invalid_input(format!("unknown measure: {value}"))),
+                });
+            }
+            "--top" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| invalid_input("--top requires a value"))?;
+                top =
+                    Some(value.parse().map_err(|_| {
+                        invalid_input(format!("invalid number of concepts: {value}"))
+                    })?);
+            }
+            "--format" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| invalid_input("--format requires a value"))?;
+                output_format = match value.as_str() {
+                    "concepts" => OutputFormat::Concepts,
+                    "dot" => OutputFormat::Dot,
+                    "svg" => OutputFormat::Svg,
+                    "json" => OutputFormat::Json,
+                    _ => return Err(invalid_input(format!("unknown output format: {value}"))),
+                };
+            }
+            "--labels" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| invalid_input("--labels requires a value"))?;
+                labels = match value.as_str() {
+                    "reduced" => LatticeLabels::Reduced,
+                    "full" => LatticeLabels::Full,
+                    "sizes" => LatticeLabels::Sizes,
+                    _ => return Err(invalid_input(format!("unknown label mode: {value}"))),
+                };
+            }
+            "--dat" => input_format = Some(InputFormat::Dat),
+            "--cxt" => input_format = Some(InputFormat::Cxt),
+            "--csv" => input_format = Some(InputFormat::Csv),
+            "--cex" => input_format = Some(InputFormat::Cex),
             _ if arg.starts_with('-') => {
                 return Err(invalid_input(format!("unknown option: {arg}")));
             }
@@ -100,15 +225,52 @@ fn parse_args(args: impl IntoIterator<Item = String>) -> io::Result<Option<Confi
         }
     }
 
+    if top.is_some() && sort_by.is_none() {
+        return Err(invalid_input("--top requires --sort-by"));
+    }
+
     Ok(Some(Config {
         count_only,
         verbose,
         output_path,
         input_format,
         input_path,
+        min_support,
+        sort_by,
+        top,
+        output_format,
+        labels,
     }))
 }
 
+// Use the format given by a flag, or else detect it from the file extension.
+fn detect_format(flag: Option<InputFormat>, path: Option<&str>) -> InputFormat {
+    flag.unwrap_or_else(|| {
+        let extension = path
+            .and_then(|path| std::path::Path::new(path).extension())
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("cxt") => InputFormat::Cxt,
+            Some("csv") => InputFormat::Csv,
+            Some("cex") => InputFormat::Cex,
+            _ => InputFormat::Dat,
+        }
+    })
+}
+
+fn parse_min_support(value: &str) -> io::Result<MinSupport> {
+    if let Ok(count) = value.parse() {
+        return Ok(MinSupport::Absolute(count));
+    }
+    match value.parse::<f64>() {
+        Ok(fraction) if (0.0..=1.0).contains(&fraction) => Ok(MinSupport::Relative(fraction)),
+        _ => Err(invalid_input(format!(
+            "invalid minimum support: {value} (expected a count or a fraction between 0 and 1)"
+        ))),
+    }
+}
+
 fn open_input(path: Option<&str>) -> io::Result<Box<dyn Read>> {
     match path {
         Some(path) => File::open(path).map(|file| Box::new(file) as Box<dyn Read>),
@@ -123,28 +285,87 @@ fn open_output(path: Option<&str>) -> io::Result<Box<dyn Write>> {
     }
 }
 
-fn write_result<A, B>(
+// The bounds that object and attribute labels need for every output format.
+#[cfg(feature = "serde")]
+trait Label: Clone + Send + Sync + Debug + std::fmt::Display + serde::Serialize {}
+#[cfg(feature = "serde")]
+impl<T: Clone + Send + Sync + Debug + std::fmt::Display + serde::Serialize> Label for T {}
+#[cfg(not(feature = "serde"))]
+trait Label: Clone + Send + Sync + Debug + std::fmt::Display {}
+#[cfg(not(feature = "serde"))]
+impl<T: Clone + Send + Sync + Debug + std::fmt::Display> Label for T {}
+
+fn write_result<A: Label, B: Label>(
     context: FormalContext<A, B>,
-    count_only: bool,
-    verbose: bool,
+    config: &Config,
     output: &mut dyn Write,
-) -> io::Result<()>
-where
-    A: Clone + Send + Sync + Debug + std::fmt::Display,
-    B: Clone + Send + Sync + Debug + std::fmt::Display,
-{
-    if count_only {
-        if verbose {
+) -> io::Result<()> {
+    let min_support = match config.min_support {
+        None => 0,
+        Some(MinSupport::Absolute(count)) => count,
+        Some(MinSupport::Relative(fraction)) => context.min_support_count(fraction),
+    };
+
+    if config.count_only {
+        if config.verbose {
+            writeln!(output, "{context}")?;
+            writeln!(output, "Reduced? {}", context.is_reduced())?;
+        }
+        return writeln!(
+            output,
+            "{}",
+            context.num_concepts_with_min_support(min_support)
+        );
+    }
+
+    if let OutputFormat::Dot | OutputFormat::Svg = config.output_format {
+        if config.min_support.is_some() {
+            return Err(invalid_input(
+                "--min-support only applies to the concepts output format",
+            ));
+        }
+        if config.sort_by.is_some() {
+            return Err(invalid_input(
+                "--sort-by only applies to the concepts and json output formats",
+            ));
+        }
+        let lattice = context.concept_lattice();
+        return match config.output_format {
+            OutputFormat::Svg => lattice.write_svg(output, config.labels),
+            _ => lattice.write_dot(output, config.labels),
+        };
+    }
+
+    if let Some(sort_by) = config.sort_by {
+        let ranked = ranked_concepts(
+            &context,
+            sort_by,
+            config.top.unwrap_or(usize::MAX),
+            min_support,
+        );
+        if let OutputFormat::Json = config.output_format {
+            let concepts: Vec<_> = ranked.into_iter().map(|(concept, _)| concept).collect();
+            return write_json(&concepts, output);
+        }
+        if config.verbose {
             writeln!(output, "{context}")?;
             writeln!(output, "Reduced? {}", context.is_reduced())?;
+            writeln!(output, "{}", ranked.len())?;
         }
-        return writeln!(output, "{}", context.num_concepts());
+        for (concept, value) in ranked {
+            writeln!(output, "{value:.4}\t{concept}")?;
+        }
+        return Ok(());
     }
 
-    if verbose {
+    if let OutputFormat::Json = config.output_format {
+        return write_json(&frequent_concepts(&context, min_support), output);
+    }
+
+    if config.verbose {
         writeln!(output, "{context}")?;
         writeln!(output, "Reduced? {}", context.is_reduced())?;
-        let concepts = context.all_concepts();
+        let concepts = frequent_concepts(&context, min_support);
         writeln!(output, "{}", concepts.len())?;
         for concept in concepts {
             writeln!(output, "{concept}")?;
@@ -152,12 +373,276 @@ where
         return Ok(());
     }
 
-    for concept in context.all_concepts() {
+    for concept in frequent_concepts(&context, min_support) {
         writeln!(output, "{concept}")?;
     }
     Ok(())
 }
 
+// Without a support threshold, keep the plain enumeration (and its output
+// order).
+fn frequent_concepts<A, B>(
+    context: &FormalContext<A, B>,
+    min_support: usize,
+) -> Vec<FormalConcept<A, B>>
+where
+    A: Clone + Send + Sync,
+    B: Clone + Send + Sync,
+{
+    if min_support == 0 {
+        context.all_concepts()
+    } else {
+        context.concepts_with_min_support(min_support)
+    }
+}
+
+// The `top` concepts with at least `min_support` objects, ranked by
+// `sort_by`, with their values.
+fn ranked_concepts<A: Label, B: Label>(
+    context: &FormalContext<A, B>,
+    sort_by: SortBy,
+    top: usize,
+    min_support: usize,
+) -> Vec<(FormalConcept<A, B>, f64)> {
+    let options = StabilityOptions::default();
+    let measure = |concept: &FormalConcept<A, B>| match sort_by {
+        SortBy::Stability => concept.intensional_stability(&options),
+        SortBy::ExtensionalStability => concept.extensional_stability(&options),
+        SortBy::Separation => concept.separation(),
+        SortBy::Support => concept.support(),
+        SortBy::Probability => concept.probability_index(),
+    };
+    // Small probability indices are the interesting ones.
+    let sign = match sort_by {
+        SortBy::Probability => -1.0,
+        _ => 1.0,
+    };
+    let key = |concept: &FormalConcept<A, B>| sign * measure(concept);
+    let mut ranked = context.top_k_concepts_with_min_support_by(min_support, key, top);
+    for (_, value) in &mut ranked {
+        *value *= sign;
+    }
+    ranked
+}
+
+#[cfg(feature = "serde")]
+fn write_json<A: Label, B: Label>(
+    concepts: &[FormalConcept<A, B>],
+    output: &mut dyn Write,
+) -> io::Result<()> {
+    serde_json::to_writer(&mut *output, concepts)?;
+    writeln!(output)
+}
+
+#[cfg(not(feature = "serde"))]
+fn write_json<A: Label, B: Label>(
+    _concepts: &[FormalConcept<A, B>],
+    _output: &mut dyn Write,
+) -> io::Result<()> {
+    Err(invalid_input(
+        "--format json requires fcars to be built with the serde feature",
+    ))
+}
+
 fn invalid_input(message: impl Into<String>) -> io::Error {
     io::Error::new(io::ErrorKind::InvalidInput, message.into())
 }
+
+fn parse_error(input_name: &str, err: ParseError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("{input_name}:{err}"))
+}
+
+// `fcars explore`: run attribute exploration, asking the user on stdin.
+fn explore(args: impl IntoIterator<Item = String>) -> io::Result<()> {
+    let mut output_path = None;
+    let mut input_format = None;
+    let mut input_path = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                return Ok(());
+            }
+            "-o" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| invalid_input("-o requires an output file path"))?;
+                if output_path.replace(path).is_some() {
+                    return Err(invalid_input("-o may only be specified once"));
+                }
+            }
+            "--dat" => input_format = Some(InputFormat::Dat),
+            "--cxt" => input_format = Some(InputFormat::Cxt),
+            "--csv" => input_format = Some(InputFormat::Csv),
+            "--cex" => input_format = Some(InputFormat::Cex),
+            _ if arg.starts_with('-') => {
+                return Err(invalid_input(format!("unknown option: {arg}")));
+            }
+            _ => {
+                if input_path.replace(arg).is_some() {
+                    return Err(invalid_input("expected at most one input file"));
+                }
+            }
+        }
+    }
+    // Answers are read from stdin, so the context has to come from a file.
+    let input_path: String =
+        input_path.ok_or_else(|| invalid_input("explore requires an input file"))?;
+
+    let input = open_input(Some(&input_path))?;
+    match detect_format(input_format, Some(&input_path)) {
+        InputFormat::Dat => {
+            let mut context =
+                FormalContext::try_from_dat(input).map_err(|err| parse_error(&input_path, err))?;
+            run_exploration(&mut context)?;
+            if let Some(path) = output_path {
+                context.write_dat(open_output(Some(&path))?)?;
+            }
+        }
+        InputFormat::Cxt => {
+            let mut context =
+                FormalContext::try_from_cxt(input).map_err(|err| parse_error(&input_path, err))?;
+            run_exploration(&mut context)?;
+            if let Some(path) = output_path {
+                context.write_cxt(open_output(Some(&path))?)?;
+            }
+        }
+        InputFormat::Csv => {
+            let options = CsvOptions::default();
+            let mut context = FormalContext::try_from_csv(input, &options)
+                .map_err(|err| parse_error(&input_path, err))?;
+            run_exploration(&mut context)?;
+            if let Some(path) = output_path {
+                context.write_csv(open_output(Some(&path))?, &options)?;
+            }
+        }
+        InputFormat::Cex => {
+            let mut context =
+                FormalContext::try_from_cex(input).map_err(|err| parse_error(&input_path, err))?;
+            run_exploration(&mut context)?;
+            if let Some(path) = output_path {
+                context.write_cex(open_output(Some(&path))?)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_exploration<B: Debug + std::fmt::Display>(
+    context: &mut FormalContext<String, B>,
+) -> io::Result<()> {
+    let mut expert = TerminalExpert {
+        input: io::stdin().lock(),
+        output: io::stdout(),
+        accepted: Vec::new(),
+    };
+    let basis = context.explore(&mut expert).map_err(|err| match err {
+        ExplorationError::Expert(err) => err,
+        err => io::Error::new(io::ErrorKind::InvalidData, err.to_string()),
+    })?;
+
+    let mut output = io::stdout();
+    writeln!(output, "Accepted implications:")?;
+    for implication in &basis {
+        writeln!(output, "{}", implication.display(context))?;
+    }
+    Ok(())
+}
+
+// An expert that asks the user. It checks counterexamples itself, so that a
+// mistyped answer leads to a new prompt rather than ending the exploration.
+struct TerminalExpert<R, W> {
+    input: R,
+    output: W,
+    accepted: Vec<Implication>,
+}
+
+impl<R: io::BufRead, W: Write> TerminalExpert<R, W> {
+    fn prompt(&mut self, message: &str) -> io::Result<String> {
+        write!(self.output, "{message}")?;
+        self.output.flush()?;
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "exploration aborted",
+            ));
+        }
+        Ok(line.trim().to_string())
+    }
+
+    // Ask for a counterexample until the user gives a valid one.
+    fn counterexample<B: Debug + std::fmt::Display>(
+        &mut self,
+        context: &FormalContext<String, B>,
+        question: &Implication,
+    ) -> io::Result<(String, BitVec)> {
+        let name = self.prompt("Name of the counterexample: ")?;
+        loop {
+            let answer = self.prompt(&format!("Attributes of {name}, separated by commas: "))?;
+            let mut intent = BitVec::repeat(false, context.attributes.len());
+            let mut unknown = Vec::new();
+            for label in answer.split(',').map(str::trim).filter(|l| !l.is_empty()) {
+                match context
+                    .attributes
+                    .iter()
+                    .position(|attribute| attribute.to_string() == label)
+                {
+                    Some(j) => intent.set(j, true),
+                    None => unknown.push(label.to_string()),
+                }
+            }
+            let refutes = !question.respected_by(&intent);
+            let violated = self
+                .accepted
+                .iter()
+                .find(|implication| !implication.respected_by(&intent));
+            if !unknown.is_empty() {
+                writeln!(self.output, "Unknown attributes: {}", unknown.join(", "))?;
+            } else if !refutes {
+                writeln!(
+                    self.output,
+                    "A counterexample needs every premise attribute and must lack a conclusion attribute."
+                )?;
+            } else if let Some(implication) = violated {
+                writeln!(
+                    self.output,
+                    "This object violates the accepted implication {}.",
+                    implication.display(context)
+                )?;
+            } else {
+                return Ok((name, intent));
+            }
+        }
+    }
+}
+
+impl<R: io::BufRead, W: Write, B: Debug + std::fmt::Display> Expert<String, B>
+    for TerminalExpert<R, W>
+{
+    type Error = io::Error;
+
+    fn ask(
+        &mut self,
+        context: &FormalContext<String, B>,
+        question: &Implication,
+    ) -> io::Result<ExpertAnswer<String>> {
+        loop {
+            let answer =
+                self.prompt(&format!("Does {} hold? [y/n] ", question.display(context)))?;
+            match answer.as_str() {
+                "y" | "yes" => {
+                    self.accepted.push(question.clone());
+                    return Ok(ExpertAnswer::Accept);
+                }
+                "n" | "no" => {
+                    let (name, intent) = self.counterexample(context, question)?;
+                    return Ok(ExpertAnswer::Counterexample(name, intent));
+                }
+                _ => writeln!(self.output, "Please answer y or n.")?,
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use bitvec::prelude::*;
+use std::cmp::Ordering;
+use std::collections::HashMap;
 
 use crate::RawFormalConcept;
 
@@ -27,24 +29,81 @@ pub(crate) fn is_subset(left: &BitVec, right: &BitVec) -> bool {
     (left_words[full_words] & !right_words[full_words] & mask) == 0
 }
 
-/// Determines if any row of the binary matrix x is an intersection of other rows
-/// If so, returns the index of the first such row
-/// Else, returns None
-/// ASSUMES x is a matrix, i.e. each bitvec in x has the same length.
-pub(crate) fn redundant_row(x: &[BitVec]) -> Option<usize> {
-    for i in 0..x.len() {
-        let mut best_approx = BitVec::repeat(true, x[0].len());
-        for j in 0..x.len() {
-            if i != j && is_subset(&x[i], &x[j]) {
-                best_approx &= &x[j];
-            }
+// Returns the mask of the rows of `rows` that are not an intersection of
+// other rows. Of several identical rows, only the last is kept (unless their
+// common value is an intersection of other rows, in which case none is), and
+// rows equal to `width` ones are the intersection of no rows.
+//
+// This is the set of rows that survives repeatedly deleting the first
+// redundant row, computed in one pass. Identical rows are merged first; then
+// the rows containing each distinct row are found by intersecting the columns
+// of its attributes, and the row is redundant exactly when they intersect to
+// it.
+pub(crate) fn irreducible_rows(rows: &[BitVec], width: usize) -> BitVec {
+    let mut last: HashMap<&BitVec, usize> = HashMap::new();
+    let mut distinct: Vec<&BitVec> = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        if last.insert(row, i).is_none() {
+            distinct.push(row);
+        }
+    }
+    let columns = transpose(
+        &distinct.iter().map(|&row| row.clone()).collect::<Vec<_>>(),
+        width,
+    );
+    let mut keep = BitVec::repeat(false, rows.len());
+    for (d, row) in distinct.iter().enumerate() {
+        let mut containing = BitVec::repeat(true, distinct.len());
+        for j in row.iter_ones() {
+            and_assign(&mut containing, &columns[j]);
         }
-        if best_approx == x[i] {
-            // Row i is the intersection of other rows
-            return Some(i);
+        let mut meet = BitVec::repeat(true, width);
+        for k in containing.iter_ones().filter(|&k| k != d) {
+            and_assign(&mut meet, distinct[k]);
+        }
+        if meet != **row {
+            keep.set(last[row], true);
         }
     }
-    None
+    keep
+}
+
+// Intersect `left` with `right`, a bitset of the same length, a word at a
+// time; the `&=` operator of `bitvec` works bit by bit.
+pub(crate) fn and_assign(left: &mut BitVec, right: &BitVec) {
+    for (l, r) in left.as_raw_mut_slice().iter_mut().zip(right.as_raw_slice()) {
+        *l &= r;
+    }
+}
+
+// For each row `x[i]`, returns the intersection of all rows that strictly
+// contain it, minus `x[i]` itself (all other columns if no row contains it).
+// These are the columns in which row `i` has a "down arrow": the entries that
+// become true in every strictly larger row. This assumes `x` is a matrix of
+// width `width`.
+pub(crate) fn strict_superset_gaps(x: &[BitVec], width: usize) -> Vec<BitVec> {
+    x.iter()
+        .map(|row| {
+            let mut meet = BitVec::repeat(true, width);
+            for other in x {
+                if other != row && is_subset(row, other) {
+                    meet &= other;
+                }
+            }
+            meet & !row.clone()
+        })
+        .collect()
+}
+
+// Transpose a matrix with `width` columns.
+pub(crate) fn transpose(x: &[BitVec], width: usize) -> Vec<BitVec> {
+    let mut transposed = vec![BitVec::with_capacity(x.len()); width];
+    for row in x {
+        for (j, bit) in row.iter().by_vals().enumerate() {
+            transposed[j].push(bit);
+        }
+    }
+    transposed
 }
 
 // A concept in the scalar fast path. Bit `i` means object/attribute `i` is
@@ -62,6 +121,7 @@ pub(crate) struct MaskContext {
     attributes_len: usize,
     object_intents: Vec<u128>,
     attribute_extents: Vec<u128>,
+    min_support: usize,
 }
 
 // A concept in the arbitrary-size dense path. These buffers may contain
@@ -84,6 +144,7 @@ pub(crate) struct DenseContext {
     attribute_extents: Vec<u128>,
     object_tail_mask: u128,
     attribute_tail_mask: u128,
+    min_support: usize,
 }
 
 impl MaskContext {
@@ -102,9 +163,18 @@ impl MaskContext {
             attributes_len,
             object_intents: object_intents.into_iter().map(bitvec_to_mask).collect(),
             attribute_extents: attribute_extents.into_iter().map(bitvec_to_mask).collect(),
+            min_support: 0,
         })
     }
 
+    // Restrict `child` to concepts whose extent has at least `min_support`
+    // objects. Extents only shrink along a branch of the search tree, so
+    // rejecting an infrequent child prunes its whole subtree.
+    pub(crate) fn with_min_support(mut self, min_support: usize) -> Self {
+        self.min_support = min_support;
+        self
+    }
+
     pub(crate) fn attributes_len(&self) -> usize {
         self.attributes_len
     }
@@ -127,9 +197,13 @@ impl MaskContext {
     }
 
     // Try to create the child obtained by adding `attribute` to `concept`.
-    // Returns `None` when the closure fails the PCbO canonicity test.
+    // Returns `None` when the closure fails the PCbO canonicity test, or when
+    // the child's extent is below the minimum support.
     pub(crate) fn child(&self, concept: MaskConcept, attribute: usize) -> Option<MaskConcept> {
         let extent = concept.extent & self.attribute_extents[attribute];
+        if (extent.count_ones() as usize) < self.min_support {
+            return None;
+        }
         let intent = self.induce_r(extent);
 
         if ((concept.intent ^ intent) & low_bits(attribute)) == 0 {
@@ -139,11 +213,16 @@ impl MaskContext {
         }
     }
 
+    // The extent of attribute `attribute`.
+    pub(crate) fn attribute_extent(&self, attribute: usize) -> u128 {
+        self.attribute_extents[attribute]
+    }
+
     // Derive the intent common to all objects in `extent`.
     //
     // `objects &= objects - 1` clears the lowest set bit, so the loop visits
     // exactly the objects in the extent without scanning every object index.
-    fn induce_r(&self, extent: u128) -> u128 {
+    pub(crate) fn induce_r(&self, extent: u128) -> u128 {
         let mut intent = low_bits(self.attributes_len);
         let mut objects = extent;
         while objects != 0 {
@@ -154,6 +233,33 @@ impl MaskContext {
         intent
     }
 
+    // Derive the extent of objects having every attribute in `intent`. This is
+    // the dual of `induce_r`, visiting only the attributes in the intent.
+    fn induce_l(&self, intent: u128) -> u128 {
+        let mut extent = low_bits(self.objects_len);
+        let mut attributes = intent;
+        while attributes != 0 {
+            let attribute = attributes.trailing_zeros() as usize;
+            extent &= self.attribute_extents[attribute];
+            attributes &= attributes - 1;
+        }
+        extent
+    }
+
+    // Close an attribute set in the context, i.e. compute `intent''`.
+    pub(crate) fn close_intent(&self, intent: u128) -> u128 {
+        self.induce_r(self.induce_l(intent))
+    }
+
+    // The concept generated by an attribute set: `(intent', intent'')`.
+    pub(crate) fn concept_of_intent(&self, intent: u128) -> MaskConcept {
+        let extent = self.induce_l(intent);
+        MaskConcept {
+            extent,
+            intent: self.induce_r(extent),
+        }
+    }
+
     // Convert a masked concept back to the public representation. This is kept
     // at the boundary so the hot closure/canonicity work can stay allocation
     // free for small contexts.
@@ -204,13 +310,26 @@ impl DenseContext {
             attribute_extents: dense_attribute_extents,
             object_tail_mask,
             attribute_tail_mask,
+            min_support: 0,
         }
     }
 
+    // Restrict `child` to concepts whose extent has at least `min_support`
+    // objects. See `MaskContext::with_min_support`.
+    pub(crate) fn with_min_support(mut self, min_support: usize) -> Self {
+        self.min_support = min_support;
+        self
+    }
+
     pub(crate) fn attributes_len(&self) -> usize {
         self.attributes_len
     }
 
+    // The number of words in an intent.
+    pub(crate) fn attr_words(&self) -> usize {
+        self.attr_words
+    }
+
     pub(crate) fn concept_has_attribute(&self, concept: &DenseConcept, attribute: usize) -> bool {
         dense_bit_is_set(&concept.intent, attribute)
     }
@@ -230,13 +349,23 @@ impl DenseContext {
     }
 
     // Try to create the child obtained by adding `attribute` to `concept`.
-    // Returns `None` when the closure fails the PCbO canonicity test.
+    // Returns `None` when the closure fails the PCbO canonicity test, or when
+    // the child's extent is below the minimum support.
     pub(crate) fn child(&self, concept: &DenseConcept, attribute: usize) -> Option<DenseConcept> {
         let attribute_extent = self.attribute_extent(attribute);
         let mut extent = Vec::with_capacity(self.obj_words);
         for (left, right) in concept.extent.iter().zip(attribute_extent) {
             extent.push(left & right);
         }
+        if self.min_support > 0
+            && extent
+                .iter()
+                .map(|word| word.count_ones() as usize)
+                .sum::<usize>()
+                < self.min_support
+        {
+            return None;
+        }
 
         let mut intent = vec![0; self.attr_words];
         self.induce_r_into(&extent, &mut intent);
@@ -263,14 +392,14 @@ impl DenseContext {
     }
 
     // Return the dense column for one attribute.
-    fn attribute_extent(&self, attribute: usize) -> &[u128] {
+    pub(crate) fn attribute_extent(&self, attribute: usize) -> &[u128] {
         let start = attribute * self.obj_words;
         &self.attribute_extents[start..start + self.obj_words]
     }
 
     // Derive the intent common to all objects in `extent`, writing into a
     // caller-provided buffer.
-    fn induce_r_into(&self, extent: &[u128], intent: &mut [u128]) {
+    pub(crate) fn induce_r_into(&self, extent: &[u128], intent: &mut [u128]) {
         intent.fill(u128::MAX);
         if let Some(last) = intent.last_mut() {
             *last &= self.attribute_tail_mask;
@@ -288,9 +417,142 @@ impl DenseContext {
             }
         }
     }
+
+    // Derive the extent of objects having every attribute in `intent`, writing
+    // into a caller-provided buffer. This is the dual of `induce_r_into`.
+    fn induce_l_into(&self, intent: &[u128], extent: &mut [u128]) {
+        extent.fill(u128::MAX);
+        if let Some(last) = extent.last_mut() {
+            *last &= self.object_tail_mask;
+        }
+
+        for (word_idx, &word) in intent.iter().enumerate() {
+            let mut attributes = word;
+            while attributes != 0 {
+                let attribute =
+                    word_idx * u128::BITS as usize + attributes.trailing_zeros() as usize;
+                let attribute_extent = self.attribute_extent(attribute);
+                for obj_word in 0..self.obj_words {
+                    extent[obj_word] &= attribute_extent[obj_word];
+                }
+                attributes &= attributes - 1;
+            }
+        }
+    }
+
+    // Close an attribute set in the context, i.e. compute `intent''`.
+    pub(crate) fn close_intent(&self, intent: &[u128]) -> Vec<u128> {
+        self.concept_of_intent(intent).intent
+    }
+
+    // The concept generated by an attribute set: `(intent', intent'')`.
+    pub(crate) fn concept_of_intent(&self, intent: &[u128]) -> DenseConcept {
+        let mut extent = vec![0; self.obj_words];
+        self.induce_l_into(intent, &mut extent);
+        let mut closed = vec![0; self.attr_words];
+        self.induce_r_into(&extent, &mut closed);
+        DenseConcept {
+            extent,
+            intent: closed,
+        }
+    }
+
+    // Convert a public `BitVec` attribute set to dense words.
+    pub(crate) fn intent_from_bitvec(&self, intent: &BitVec) -> Vec<u128> {
+        bitvec_to_dense_words(intent, self.attr_words, self.attribute_tail_mask)
+    }
+
+    // Convert a dense attribute set to a public `BitVec`.
+    pub(crate) fn intent_to_bitvec(&self, intent: &[u128]) -> BitVec {
+        dense_words_to_bitvec(intent, self.attributes_len)
+    }
+}
+
+// One step of Ganter's NextClosure on `u128` masks over `len` elements.
+//
+// Sets are compared lectically, with element `i` more significant than every
+// element after it. Returns the lectically next set after `current` that is
+// closed under `close`, or `None` if `current` is the last one. `current`
+// itself need not be closed; this lets callers change `close` between steps,
+// as the canonical basis computation does.
+pub(crate) fn mask_next_closure(
+    current: u128,
+    len: usize,
+    mut close: impl FnMut(u128) -> u128,
+) -> Option<u128> {
+    for i in (0..len).rev() {
+        if bit_is_set(current, i) {
+            continue;
+        }
+        let candidate = close((current & low_bits(i)) | (1_u128 << i));
+        if (candidate & !current & low_bits(i)) == 0 {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+// Multi-word analogue of `mask_next_closure`.
+pub(crate) fn dense_next_closure(
+    current: &[u128],
+    len: usize,
+    mut close: impl FnMut(&[u128]) -> Vec<u128>,
+) -> Option<Vec<u128>> {
+    let mut seed = vec![0; current.len()];
+    for i in (0..len).rev() {
+        if dense_bit_is_set(current, i) {
+            continue;
+        }
+        let word = i / u128::BITS as usize;
+        let bit = i % u128::BITS as usize;
+        seed[..word].copy_from_slice(&current[..word]);
+        seed[word] = (current[word] & low_bits(bit)) | (1_u128 << bit);
+        seed[word + 1..].fill(0);
+        let candidate = close(&seed);
+        if dense_prefix_eq(current, &candidate, i) {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+// `BitVec` analogue of `mask_next_closure`, for callers whose closure cannot
+// run on the `u128` engines (e.g. because the context grows between steps).
+pub(crate) fn bitvec_next_closure(
+    current: &BitVec,
+    mut close: impl FnMut(&BitVec) -> BitVec,
+) -> Option<BitVec> {
+    for i in (0..current.len()).rev() {
+        if current[i] {
+            continue;
+        }
+        let mut seed = current.clone();
+        seed[i..].fill(false);
+        seed.set(i, true);
+        let candidate = close(&seed);
+        if candidate[..i] == current[..i] {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+// Compare two sets lectically, as NextClosure enumerates them: the first
+// element in which they differ decides, and the set containing it is larger.
+pub(crate) fn lectic_cmp(x: &BitVec, y: &BitVec) -> Ordering {
+    match x.iter().zip(y.iter()).position(|(a, b)| a != b) {
+        Some(i) if y[i] => Ordering::Less,
+        Some(_) => Ordering::Greater,
+        None => Ordering::Equal,
+    }
 }
 
-fn bitvec_to_mask(bits: &BitVec) -> u128 {
+// Whether every bit of `left` is also set in `right`.
+pub(crate) fn dense_is_subset(left: &[u128], right: &[u128]) -> bool {
+    left.iter().zip(right).all(|(l, r)| l & !r == 0)
+}
+
+pub(crate) fn bitvec_to_mask(bits: &BitVec) -> u128 {
     bits.iter_ones().fold(0, |mask, bit| mask | (1_u128 << bit))
 }
 
@@ -342,7 +604,7 @@ fn dense_bit_is_set(words: &[u128], bit: usize) -> bool {
     bit_is_set(words[bit / u128::BITS as usize], bit % u128::BITS as usize)
 }
 
-fn dense_prefix_eq(a: &[u128], b: &[u128], bits: usize) -> bool {
+pub(crate) fn dense_prefix_eq(a: &[u128], b: &[u128], bits: usize) -> bool {
     let whole_words = bits / u128::BITS as usize;
     let rem_bits = bits % u128::BITS as usize;
 
@@ -353,6 +615,18 @@ fn dense_prefix_eq(a: &[u128], b: &[u128], bits: usize) -> bool {
     rem_bits == 0 || ((a[whole_words] ^ b[whole_words]) & low_bits(rem_bits)) == 0
 }
 
+// Whether the first `bits` bits of `a` are a subset of those of `b`.
+pub(crate) fn dense_prefix_subset(a: &[u128], b: &[u128], bits: usize) -> bool {
+    let whole_words = bits / u128::BITS as usize;
+    let rem_bits = bits % u128::BITS as usize;
+
+    if !dense_is_subset(&a[..whole_words], &b[..whole_words]) {
+        return false;
+    }
+
+    rem_bits == 0 || (a[whole_words] & !b[whole_words] & low_bits(rem_bits)) == 0
+}
+
 fn words_for_bits(bits: usize) -> usize {
     bits.div_ceil(u128::BITS as usize)
 }
@@ -0,0 +1,211 @@
+use crate::FormalContext;
+use crate::Implication;
+use crate::bit_fiddling::*;
+
+// The canonical basis is computed with Ganter's algorithm: NextClosure
+// enumerates, in lectic order, the attribute sets that are closed under the
+// implications found so far. Each such set is either an intent or a
+// pseudo-intent; the pseudo-intents are exactly the sets that differ from
+// their closure in the context, and each one contributes the implication
+// `P => P''`. Implications are only ever added for premises lectically
+// smaller than the current set, so the changing closure operator does not
+// disturb the enumeration.
+//
+// Both the implication closure and the context closure run on the `u128`
+// engines from `bit_fiddling.rs`, using the scalar path when the context fits
+// and the multi-word path otherwise, just like PCbO.
+
+impl<A: Sync, B: Sync> FormalContext<A, B> {
+    /// Computes the canonical (Duquenne–Guigues) basis of the attribute
+    /// implications of this context.
+    ///
+    /// The basis is the unique minimal set of implications from which every
+    /// implication valid in the context follows. Its premises are the
+    /// pseudo-intents of the context, and they are returned in lectic order.
+    /// Each conclusion contains only the attributes implied by the premise
+    /// that are not already in it, i.e. `premise'' \ premise`.
+    pub fn canonical_basis(&self) -> Vec<Implication> {
+        if let Some(context) = self.mask_context() {
+            let attributes_len = self.attributes.len();
+            return canonical_basis_masked(&context)
+                .into_iter()
+                .map(|(premise, closure)| Implication {
+                    premise: mask_to_bitvec(premise, attributes_len),
+                    conclusion: mask_to_bitvec(closure & !premise, attributes_len),
+                })
+                .collect();
+        }
+
+        let context = self.dense_context();
+        canonical_basis_dense(&context)
+            .into_iter()
+            .map(|(premise, closure)| {
+                let premise = context.intent_to_bitvec(&premise);
+                let conclusion = context.intent_to_bitvec(&closure) & !premise.clone();
+                Implication {
+                    premise,
+                    conclusion,
+                }
+            })
+            .collect()
+    }
+}
+
+// Returns `(pseudo-intent, closure)` pairs for a masked context.
+fn canonical_basis_masked(context: &MaskContext) -> Vec<(u128, u128)> {
+    let mut basis = Vec::new();
+    // With no implications yet, the empty set is trivially closed.
+    let mut current = 0;
+    loop {
+        let closure = context.close_intent(current);
+        if closure != current {
+            basis.push((current, closure));
+        }
+        match mask_next_closure(current, context.attributes_len(), |set| {
+            mask_implication_closure(&basis, set)
+        }) {
+            Some(next) => current = next,
+            None => return basis,
+        }
+    }
+}
+
+// Returns `(pseudo-intent, closure)` pairs for a dense context.
+fn canonical_basis_dense(context: &DenseContext) -> Vec<(Vec<u128>, Vec<u128>)> {
+    let mut basis: Vec<(Vec<u128>, Vec<u128>)> = Vec::new();
+    let mut current = vec![0; context.attributes_len().div_ceil(u128::BITS as usize)];
+    loop {
+        let closure = context.close_intent(&current);
+        if closure != current {
+            basis.push((current.clone(), closure));
+        }
+        match dense_next_closure(&current, context.attributes_len(), |set| {
+            dense_implication_closure(&basis, set)
+        }) {
+            Some(next) => current = next,
+            None => return basis,
+        }
+    }
+}
+
+// Close `set` under `implications` by applying them until nothing changes.
+fn mask_implication_closure(implications: &[(u128, u128)], mut set: u128) -> u128 {
+    loop {
+        let before = set;
+        for &(premise, conclusion) in implications {
+            if premise & !set == 0 {
+                set |= conclusion;
+            }
+        }
+        if set == before {
+            return set;
+        }
+    }
+}
+
+// Multi-word analogue of `mask_implication_closure`.
+fn dense_implication_closure(implications: &[(Vec<u128>, Vec<u128>)], set: &[u128]) -> Vec<u128> {
+    let mut set = set.to_vec();
+    loop {
+        let mut changed = false;
+        for (premise, conclusion) in implications {
+            if dense_is_subset(premise, &set) && !dense_is_subset(conclusion, &set) {
+                for (word, conclusion_word) in set.iter_mut().zip(conclusion) {
+                    *word |= conclusion_word;
+                }
+                changed = true;
+            }
+        }
+        if !changed {
+            return set;
+        }
+    }
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ImplicationSet;
+    use crate::test_support::lives_in_water;
+    use bitvec::prelude::*;
+
+    // Checks that the closed sets of `basis` are exactly the intents of
+    // `context`, by trying every attribute set.
+    fn assert_is_basis<A, B>(context: &FormalContext<A, B>, basis: &[Implication]) {
+        let attributes_len = context.attributes.len();
+        let basis = ImplicationSet::from_implications(attributes_len, basis.to_vec());
+        for mask in 0..(1_u128 << attributes_len) {
+            let set = mask_to_bitvec(mask, attributes_len);
+            assert_eq!(basis.close(&set), context.induce_r(&context.induce_l(&set)));
+        }
+    }
+
+    #[test]
+    fn test_canonical_basis_small() {
+        let context = FormalContext::new(
+            vec![0, 1, 2],
+            vec![0, 1, 2],
+            vec![bitvec![1, 0, 1], bitvec![1, 1, 1], bitvec![0, 1, 1]],
+        );
+
+        let basis = context.canonical_basis();
+
+        assert_eq!(
+            basis,
+            vec![Implication {
+                premise: bitvec![0, 0, 0],
+                conclusion: bitvec![0, 0, 1],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_canonical_basis_lives_in_water() {
+        let context = lives_in_water();
+
+        let basis = context.canonical_basis();
+
+        assert!(
+            basis
+                .iter()
+                .all(|implication| implication.holds_in(&context))
+        );
+        assert!(basis.iter().all(|implication| implication.conclusion.any()
+            && (implication.conclusion.clone() & &implication.premise).not_any()));
+        assert_is_basis(&context, &basis);
+    }
+
+    #[test]
+    fn test_canonical_basis_dense_path_matches_masked() {
+        let masked = lives_in_water();
+        // Repeating rows changes neither the intents nor the basis.
+        let repeated: Vec<BitVec> = (0..17 * masked.objects.len())
+            .map(|i| masked.get_object_intent(i % masked.objects.len()).clone())
+            .collect();
+        let dense = FormalContext::new(
+            (0..repeated.len()).collect(),
+            masked.attributes.clone(),
+            repeated,
+        );
+
+        assert_eq!(dense.canonical_basis(), masked.canonical_basis());
+    }
+
+    #[test]
+    fn test_implication_display() {
+        let context = FormalContext::new(
+            vec!["x", "y"],
+            vec!["a", "b", "c"],
+            vec![bitvec![1, 1, 0], bitvec![1, 1, 1]],
+        );
+
+        let basis = context.canonical_basis();
+
+        assert_eq!(basis.len(), 1);
+        assert_eq!(
+            basis[0].display(&context).to_string(),
+            r#"[] => ["a", "b"]"#
+        );
+    }
+}
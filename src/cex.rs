@@ -0,0 +1,579 @@
+use crate::FormalContext;
+use crate::xml::escape_xml;
+use crate::{ParseError, ParseErrorKind};
+
+use bitvec::prelude::*;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::{self, Read, Write};
+
+// ConExp stores contexts as XML:
+//
+// <ConceptualSystem>
+//   <Version MajorNumber="1" MinorNumber="0"/>
+//   <Contexts>
+//     <Context Identifier="0" Type="Binary">
+//       <Attributes>
+//         <Attribute Identifier="0"><Name>a</Name></Attribute>
+//       </Attributes>
+//       <Objects>
+//         <Object>
+//           <Name>x</Name>
+//           <Intent><HasAttribute AttributeIdentifier="0"/></Intent>
+//         </Object>
+//       </Objects>
+//     </Context>
+//   </Contexts>
+// </ConceptualSystem>
+//
+// Only this small subset of XML is needed, so the reader is a hand-written
+// parser producing a tree of elements, rather than a dependency. It handles
+// the prolog, comments, processing instructions, CDATA sections and the
+// predefined and numeric character references; it does not validate against
+// a DTD. Elements that the format does not use (such as line diagrams saved
+// by ConExp) are parsed and ignored.
+//
+// Elements are parsed recursively, so their nesting depth is limited to keep
+// hostile input from overflowing the stack; ConExp itself nests elements only
+// a handful of levels deep.
+const MAX_DEPTH: usize = 256;
+
+impl FormalContext {
+    /// Loads a formal context from ConExp `.cex` input.
+    ///
+    /// This is the panicking counterpart of [`FormalContext::try_from_cex`];
+    /// see there for the expected format.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the input cannot be read or is malformed.
+    pub fn from_cex(input: impl Read) -> Self {
+        Self::try_from_cex(input).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Loads a formal context from ConExp `.cex` input, the XML format of
+    /// ConExp and ConExp-NG.
+    ///
+    /// The first `Context` element of the file is read. Attributes are
+    /// matched to the `HasAttribute` entries of each object's `Intent` by
+    /// their `Identifier`. Names are trimmed of surrounding whitespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseError`] if the input cannot be read, is not
+    /// well-formed XML, lacks a required element or identifier, defines an
+    /// attribute identifier twice, or refers to an attribute identifier that
+    /// is not defined.
+    pub fn try_from_cex(mut input: impl Read) -> Result<Self, ParseError> {
+        let mut text = String::new();
+        input
+            .read_to_string(&mut text)
+            .map_err(|err| ParseError::io(1, err))?;
+        let root = XmlReader::new(&text).document()?;
+
+        if root.name != "ConceptualSystem" {
+            return Err(root.missing("ConceptualSystem"));
+        }
+        let context = root.required_child("Contexts")?.required_child("Context")?;
+
+        let mut attributes = Vec::new();
+        let mut identifiers = HashMap::new();
+        for attribute in context
+            .child("Attributes")
+            .into_iter()
+            .flat_map(|element| element.children_named("Attribute"))
+        {
+            let identifier = attribute.required_attribute("Identifier")?;
+            // A repeated identifier would silently move earlier relation
+            // entries to the later attribute.
+            if identifiers.insert(identifier, attributes.len()).is_some() {
+                return Err(ParseError::new(
+                    ParseErrorKind::InvalidToken,
+                    attribute.line,
+                    attribute.column,
+                )
+                .with_token(identifier));
+            }
+            attributes.push(attribute.required_child("Name")?.text.trim().to_string());
+        }
+
+        let mut objects = Vec::new();
+        let mut relation = Vec::new();
+        for object in context
+            .child("Objects")
+            .into_iter()
+            .flat_map(|element| element.children_named("Object"))
+        {
+            objects.push(object.required_child("Name")?.text.trim().to_string());
+            let mut intent = BitVec::repeat(false, attributes.len());
+            for has in object
+                .child("Intent")
+                .into_iter()
+                .flat_map(|element| element.children_named("HasAttribute"))
+            {
+                let identifier = has.required_attribute("AttributeIdentifier")?;
+                let j = identifiers.get(identifier).ok_or_else(|| {
+                    ParseError::new(ParseErrorKind::InvalidToken, has.line, has.column)
+                        .with_token(identifier)
+                })?;
+                intent.set(*j, true);
+            }
+            relation.push(intent);
+        }
+        Ok(Self::new(objects, attributes, relation))
+    }
+}
+
+impl<A: Display, B: Display> FormalContext<A, B> {
+    /// Writes this context in ConExp `.cex` format, which can be opened by
+    /// ConExp and ConExp-NG and read back with
+    /// [`FormalContext::try_from_cex`].
+    ///
+    /// Attribute identifiers are the attribute indices.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error produced by `output`.
+    pub fn write_cex(&self, mut output: impl Write) -> io::Result<()> {
+        writeln!(output, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(output, "<ConceptualSystem>")?;
+        writeln!(output, r#"  <Version MajorNumber="1" MinorNumber="0" />"#)?;
+        writeln!(output, "  <Contexts>")?;
+        writeln!(output, r#"    <Context Identifier="0" Type="Binary">"#)?;
+        writeln!(output, "      <Attributes>")?;
+        for (j, attribute) in self.attributes.iter().enumerate() {
+            writeln!(output, r#"        <Attribute Identifier="{j}">"#)?;
+            writeln!(
+                output,
+                "          <Name>{}</Name>",
+                escape_xml(&attribute.to_string())
+            )?;
+            writeln!(output, "        </Attribute>")?;
+        }
+        writeln!(output, "      </Attributes>")?;
+        writeln!(output, "      <Objects>")?;
+        for (i, object) in self.objects.iter().enumerate() {
+            writeln!(output, "        <Object>")?;
+            writeln!(
+                output,
+                "          <Name>{}</Name>",
+                escape_xml(&object.to_string())
+            )?;
+            writeln!(output, "          <Intent>")?;
+            for j in self.get_object_intent(i).iter_ones() {
+                writeln!(
+                    output,
+                    r#"            <HasAttribute AttributeIdentifier="{j}" />"#
+                )?;
+            }
+            writeln!(output, "          </Intent>")?;
+            writeln!(output, "        </Object>")?;
+        }
+        writeln!(output, "      </Objects>")?;
+        writeln!(output, "    </Context>")?;
+        writeln!(output, "  </Contexts>")?;
+        writeln!(output, "</ConceptualSystem>")?;
+        output.flush()
+    }
+}
+
+// An XML element with its attributes, child elements and concatenated text,
+// and the 1-based position of its start tag.
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+    line: usize,
+    column: usize,
+}
+
+impl Element {
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    fn required_child(&self, name: &str) -> Result<&Element, ParseError> {
+        self.child(name).ok_or_else(|| self.missing(name))
+    }
+
+    fn required_attribute(&self, name: &str) -> Result<&str, ParseError> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+            .ok_or_else(|| self.missing(name))
+    }
+
+    fn missing(&self, name: &str) -> ParseError {
+        ParseError::new(ParseErrorKind::MissingElement, self.line, self.column).with_token(name)
+    }
+}
+
+// A cursor over XML text that tracks the current line and column.
+struct XmlReader<'a> {
+    text: &'a str,
+    pos: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> XmlReader<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            pos: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn advance(&mut self, bytes: usize) {
+        for ch in self.text[self.pos..self.pos + bytes].chars() {
+            if ch == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.pos += bytes;
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError::new(kind, self.line, self.column)
+    }
+
+    // An error at the current character: unexpected end of input, or an
+    // unexpected character.
+    fn unexpected(&self) -> ParseError {
+        match self.rest().chars().next() {
+            None => self.error(ParseErrorKind::Truncated),
+            Some(ch) => self.error(ParseErrorKind::BadCharacter).with_token(ch),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start().len();
+        self.advance(self.rest().len() - trimmed);
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), ParseError> {
+        if !self.rest().starts_with(literal) {
+            return Err(self.unexpected());
+        }
+        self.advance(literal.len());
+        Ok(())
+    }
+
+    // Skip past the next occurrence of `terminator`.
+    fn skip_past(&mut self, terminator: &str) -> Result<&'a str, ParseError> {
+        let Some(end) = self.rest().find(terminator) else {
+            return Err(self.error(ParseErrorKind::Truncated));
+        };
+        let skipped = &self.rest()[..end];
+        self.advance(end + terminator.len());
+        Ok(skipped)
+    }
+
+    // Skip a comment, processing instruction or declaration, if one starts
+    // here.
+    fn skip_markup(&mut self) -> Result<bool, ParseError> {
+        if self.rest().starts_with("<!--") {
+            self.skip_past("-->")?;
+        } else if self.rest().starts_with("<?") {
+            self.skip_past("?>")?;
+        } else if self.rest().starts_with("<!") && !self.rest().starts_with("<![CDATA[") {
+            self.skip_past(">")?;
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    fn document(&mut self) -> Result<Element, ParseError> {
+        // Skip a byte order mark.
+        if self.rest().starts_with('\u{feff}') {
+            self.advance('\u{feff}'.len_utf8());
+        }
+        loop {
+            self.skip_whitespace();
+            if !self.skip_markup()? {
+                break;
+            }
+        }
+        let root = self.element(1)?;
+        loop {
+            self.skip_whitespace();
+            if !self.skip_markup()? {
+                break;
+            }
+        }
+        if !self.rest().is_empty() {
+            return Err(self.unexpected());
+        }
+        Ok(root)
+    }
+
+    fn name(&mut self) -> Result<String, ParseError> {
+        let end = self
+            .rest()
+            .find(|ch: char| ch.is_whitespace() || "/>=<\"'".contains(ch))
+            .unwrap_or(self.rest().len());
+        if end == 0 {
+            return Err(self.unexpected());
+        }
+        let name = self.rest()[..end].to_string();
+        self.advance(end);
+        Ok(name)
+    }
+
+    // Parse the element starting here, which is nested `depth` levels deep.
+    fn element(&mut self, depth: usize) -> Result<Element, ParseError> {
+        let (line, column) = (self.line, self.column);
+        self.expect("<")?;
+        let name = self.name()?;
+        if depth > MAX_DEPTH {
+            return Err(ParseError::new(ParseErrorKind::TooDeep, line, column).with_token(name));
+        }
+        let mut element = Element {
+            name,
+            attributes: Vec::new(),
+            children: Vec::new(),
+            text: String::new(),
+            line,
+            column,
+        };
+
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.advance(2);
+                return Ok(element);
+            }
+            if self.rest().starts_with('>') {
+                self.advance(1);
+                break;
+            }
+            let key = self.name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => return Err(self.unexpected()),
+            };
+            self.advance(1);
+            let (value_line, value_column) = (self.line, self.column);
+            let raw = self.skip_past(&quote.to_string())?;
+            let value = decode_entities(raw, value_line, value_column)?;
+            element.attributes.push((key, value));
+        }
+
+        loop {
+            if self.rest().starts_with("</") {
+                let (end_line, end_column) = (self.line, self.column);
+                self.advance(2);
+                let name = self.name()?;
+                if name != element.name {
+                    return Err(ParseError::new(
+                        ParseErrorKind::InvalidToken,
+                        end_line,
+                        end_column,
+                    )
+                    .with_token(name));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                return Ok(element);
+            }
+            if self.rest().starts_with("<![CDATA[") {
+                self.advance("<![CDATA[".len());
+                element.text.push_str(self.skip_past("]]>")?);
+            } else if self.skip_markup()? {
+                continue;
+            } else if self.rest().starts_with('<') {
+                element.children.push(self.element(depth + 1)?);
+            } else if self.rest().is_empty() {
+                return Err(self.error(ParseErrorKind::Truncated));
+            } else {
+                let (text_line, text_column) = (self.line, self.column);
+                let end = self.rest().find('<').unwrap_or(self.rest().len());
+                let raw = &self.rest()[..end];
+                self.advance(end);
+                element
+                    .text
+                    .push_str(&decode_entities(raw, text_line, text_column)?);
+            }
+        }
+    }
+}
+
+// Replace entity and character references in `raw`, which starts at the
+// given position.
+fn decode_entities(raw: &str, line: usize, column: usize) -> Result<String, ParseError> {
+    let mut decoded = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let bad = || {
+            let token = rest.split_inclusive(';').next().unwrap_or(rest);
+            ParseError::new(ParseErrorKind::InvalidToken, line, column).with_token(token)
+        };
+        let end = rest.find(';').ok_or_else(bad)?;
+        let entity = &rest[1..end];
+        let ch = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(decimal) = entity.strip_prefix('#') {
+                    decimal.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32).ok_or_else(bad)?
+            }
+        };
+        decoded.push(ch);
+        rest = &rest[end + 1..];
+    }
+    decoded.push_str(rest);
+    Ok(decoded)
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- Saved by ConExp -->
+<ConceptualSystem>
+  <Version MajorNumber="1" MinorNumber="0" />
+  <Contexts>
+    <Context Identifier="0" Type="Binary">
+      <Attributes>
+        <Attribute Identifier="a1">
+          <Name>lives in water</Name>
+        </Attribute>
+        <Attribute Identifier='a2'><Name>has &lt;limbs&gt; &amp; &#x41;</Name></Attribute>
+      </Attributes>
+      <Objects>
+        <Object>
+          <Name>frog</Name>
+          <Intent>
+            <HasAttribute AttributeIdentifier="a2" />
+            <HasAttribute AttributeIdentifier="a1" />
+          </Intent>
+        </Object>
+        <Object>
+          <Name><![CDATA[fish <leech>]]></Name>
+          <Intent>
+            <HasAttribute AttributeIdentifier="a1" />
+          </Intent>
+        </Object>
+        <Object>
+          <Name>stone</Name>
+        </Object>
+      </Objects>
+    </Context>
+  </Contexts>
+  <Lattices />
+</ConceptualSystem>
+"#;
+
+    #[test]
+    fn test_from_cex() {
+        let context = FormalContext::from_cex(EXAMPLE.as_bytes());
+
+        assert_eq!(context.objects, ["frog", "fish <leech>", "stone"]);
+        assert_eq!(context.attributes, ["lives in water", "has <limbs> & A"]);
+        assert_eq!(context.get_object_intent(0), &bitvec![1, 1]);
+        assert_eq!(context.get_object_intent(1), &bitvec![1, 0]);
+        assert_eq!(context.get_object_intent(2), &bitvec![0, 0]);
+    }
+
+    #[test]
+    fn test_write_cex_round_trips() {
+        let context = FormalContext::from_cex(EXAMPLE.as_bytes());
+
+        let mut output = Vec::new();
+        context.write_cex(&mut output).unwrap();
+
+        assert_eq!(FormalContext::from_cex(output.as_slice()), context);
+    }
+
+    #[test]
+    fn test_try_from_cex_errors() {
+        let error = |input: &str| FormalContext::try_from_cex(input.as_bytes()).unwrap_err();
+
+        let err = error("<ConceptualSystem>\n  <Contexts>\n");
+        assert_eq!(err.kind(), ParseErrorKind::Truncated);
+        assert_eq!(err.line(), 3);
+
+        let err = error("<ConceptualSystem>\n  <Contexts></Context>");
+        assert_eq!(err.kind(), ParseErrorKind::InvalidToken);
+        assert_eq!((err.line(), err.column()), (2, 13));
+        assert_eq!(err.token(), Some("Context"));
+
+        let err = error("<ConceptualSystem><Contexts/></ConceptualSystem>");
+        assert_eq!(err.kind(), ParseErrorKind::MissingElement);
+        assert_eq!((err.line(), err.column()), (1, 19));
+        assert_eq!(err.token(), Some("Context"));
+
+        let err = error(concat!(
+            "<ConceptualSystem><Contexts><Context><Objects>\n",
+            "<Object><Name>x</Name><Intent><HasAttribute AttributeIdentifier=\"9\"/></Intent></Object>\n",
+            "</Objects></Context></Contexts></ConceptualSystem>",
+        ));
+        assert_eq!(err.kind(), ParseErrorKind::InvalidToken);
+        assert_eq!((err.line(), err.column()), (2, 31));
+        assert_eq!(err.token(), Some("9"));
+
+        let err = error(concat!(
+            "<ConceptualSystem><Contexts><Context><Attributes>\n",
+            "<Attribute Identifier=\"0\"><Name>a</Name></Attribute>\n",
+            "  <Attribute Identifier=\"0\"><Name>b</Name></Attribute>\n",
+            "</Attributes></Context></Contexts></ConceptualSystem>",
+        ));
+        assert_eq!(err.kind(), ParseErrorKind::InvalidToken);
+        assert_eq!((err.line(), err.column()), (3, 3));
+        assert_eq!(err.token(), Some("0"));
+
+        let err = error("<ConceptualSystem a=b/>");
+        assert_eq!(err.kind(), ParseErrorKind::BadCharacter);
+        assert_eq!(err.token(), Some("b"));
+
+        let err = error("<ConceptualSystem>&bogus;</ConceptualSystem>");
+        assert_eq!(err.kind(), ParseErrorKind::InvalidToken);
+        assert_eq!(err.token(), Some("&bogus;"));
+    }
+
+    #[test]
+    fn test_try_from_cex_deep_nesting() {
+        let input = format!(
+            "<ConceptualSystem><Contexts><Context>{}",
+            "<a>".repeat(200_000)
+        );
+
+        let err = FormalContext::try_from_cex(input.as_bytes()).unwrap_err();
+
+        assert_eq!(err.kind(), ParseErrorKind::TooDeep);
+        assert_eq!(err.token(), Some("a"));
+        assert_eq!((err.line(), err.column()), (1, 38 + 3 * (MAX_DEPTH - 3)));
+    }
+}
@@ -0,0 +1,209 @@
+use crate::FormalContext;
+use crate::pcbo::{CbOSearch, SubtreeIter, collect_all, count_all};
+
+use bitvec::prelude::*;
+use std::sync::Arc;
+
+/// A closure operator on the subsets of `{0, ..., n - 1}`, represented as
+/// bitsets of length `n`.
+///
+/// Implementations must be extensive (`set ⊆ close(set)`), monotone
+/// (`close(a) ⊆ close(b)` whenever `a ⊆ b`) and idempotent. The closed sets
+/// of such an operator form a closure system, and they are enumerated by the
+/// same parallel Close-by-One search that computes concepts: see
+/// [`ClosureOperator::closed_sets`].
+///
+/// [`FormalContext`] implements this trait with the closure `B ↦ B''` on its
+/// attributes, whose closed sets are its intents, and
+/// [`ImplicationSet`](crate::ImplicationSet) with the closure under its
+/// implications.
+pub trait ClosureOperator: Sync {
+    /// Returns `n`, the number of elements closed sets are drawn from.
+    fn elements_len(&self) -> usize;
+
+    /// Returns the closure of `set`, a bitset of length
+    /// [`ClosureOperator::elements_len`].
+    fn close(&self, set: &BitVec) -> BitVec;
+
+    /// Enumerates all closed sets in parallel.
+    ///
+    /// Every candidate is closed once with [`ClosureOperator::close`], so the
+    /// cost is dominated by the closure. Order is an implementation detail and
+    /// should not be relied on.
+    fn closed_sets(&self) -> Vec<BitVec> {
+        collect_all(&ClosureSearch(self), |set| set)
+    }
+
+    /// Counts the closed sets.
+    ///
+    /// See [`ClosureOperator::closed_sets`] for details.
+    fn num_closed_sets(&self) -> usize {
+        count_all(&ClosureSearch(self))
+    }
+
+    /// Returns a sequential iterator over all closed sets.
+    ///
+    /// Closed sets are generated lazily on the calling thread, so consumers
+    /// can stop early without paying for the rest of the closure system.
+    fn closed_sets_iter(&self) -> impl Iterator<Item = BitVec> + '_
+    where
+        Self: Sized,
+    {
+        let search = Arc::new(ClosureSearch(self));
+        let root = search.root();
+        SubtreeIter::new(search, root, 0, |set: &BitVec| set.clone())
+    }
+}
+
+impl<A: Sync, B: Sync> ClosureOperator for FormalContext<A, B> {
+    fn elements_len(&self) -> usize {
+        self.attributes.len()
+    }
+
+    fn close(&self, set: &BitVec) -> BitVec {
+        self.induce_r(&self.induce_l(set))
+    }
+
+    // The intents of a context are better enumerated by the dedicated PCbO
+    // engines, which compute closures from extents.
+    fn closed_sets(&self) -> Vec<BitVec> {
+        self.all_concepts_raw()
+            .into_iter()
+            .map(|concept| concept.intent)
+            .collect()
+    }
+
+    fn num_closed_sets(&self) -> usize {
+        self.num_concepts()
+    }
+}
+
+// Close-by-One over the closed sets of a `ClosureOperator`. A child adds
+// element `j` to a closed set and closes the result; it is canonical when the
+// closure adds no element before `j`.
+pub(crate) struct ClosureSearch<'a, C: ?Sized>(&'a C);
+
+impl<C: ClosureOperator + ?Sized> CbOSearch for ClosureSearch<'_, C> {
+    type Node = BitVec;
+
+    fn elements_len(&self) -> usize {
+        self.0.elements_len()
+    }
+
+    fn root(&self) -> BitVec {
+        self.0.close(&bitvec![0; self.0.elements_len()])
+    }
+
+    fn node_contains(&self, set: &BitVec, element: usize) -> bool {
+        set[element]
+    }
+
+    fn canonical_child(&self, set: &BitVec, element: usize) -> Option<BitVec> {
+        let mut child = set.clone();
+        child.set(element, true);
+        let child = self.0.close(&child);
+        (child[..element] == set[..element]).then_some(child)
+    }
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Implication;
+    use crate::ImplicationSet;
+
+    // The convex subsets of the chain 0 < 1 < ... < n - 1: the closure of a
+    // set is the interval between its smallest and largest element.
+    struct Intervals(usize);
+
+    impl ClosureOperator for Intervals {
+        fn elements_len(&self) -> usize {
+            self.0
+        }
+
+        fn close(&self, set: &BitVec) -> BitVec {
+            let mut closure = bitvec![0; self.0];
+            if let (Some(first), Some(last)) = (set.first_one(), set.last_one()) {
+                closure[first..=last].fill(true);
+            }
+            closure
+        }
+    }
+
+    #[test]
+    fn test_closed_sets_of_custom_operator() {
+        let intervals = Intervals(6);
+        let mut closed = intervals.closed_sets();
+        closed.sort();
+
+        // The empty set and one interval per pair `first <= last`.
+        assert_eq!(closed.len(), 1 + 6 * 7 / 2);
+        assert_eq!(intervals.num_closed_sets(), closed.len());
+        let mut sequential: Vec<BitVec> = intervals.closed_sets_iter().collect();
+        sequential.sort();
+        assert_eq!(sequential, closed);
+        for set in &closed {
+            assert_eq!(&intervals.close(set), set);
+        }
+    }
+
+    #[test]
+    fn test_context_closed_sets_are_intents() {
+        let context = FormalContext::new(
+            vec!["a", "b", "c"],
+            vec!["1", "2", "3", "4"],
+            vec![
+                bitvec![1, 1, 0, 0],
+                bitvec![0, 1, 1, 0],
+                bitvec![1, 1, 1, 0],
+            ],
+        );
+        let mut expected: Vec<BitVec> = context
+            .all_concepts_raw()
+            .into_iter()
+            .map(|concept| concept.intent)
+            .collect();
+        expected.sort();
+
+        // Bypass the override to exercise the generic search.
+        let mut generic = collect_all(&ClosureSearch(&context), |set| set);
+        generic.sort();
+        assert_eq!(generic, expected);
+        let mut sequential: Vec<BitVec> = context.closed_sets_iter().collect();
+        sequential.sort();
+        assert_eq!(sequential, expected);
+        assert_eq!(context.num_closed_sets(), expected.len());
+    }
+
+    #[test]
+    fn test_implication_closed_sets_match_context() {
+        // The canonical basis of a context generates exactly its intents.
+        let context = FormalContext::new(
+            vec!["a", "b", "c", "d"],
+            vec!["1", "2", "3", "4", "5"],
+            vec![
+                bitvec![1, 1, 0, 0, 1],
+                bitvec![0, 1, 1, 0, 1],
+                bitvec![1, 0, 1, 1, 0],
+                bitvec![0, 0, 0, 1, 1],
+            ],
+        );
+        let basis = ImplicationSet::from_implications(5, context.canonical_basis());
+        let mut expected = context.closed_sets();
+        expected.sort();
+
+        let mut closed = basis.closed_sets();
+        closed.sort();
+        assert_eq!(closed, expected);
+
+        let unrelated = ImplicationSet::from_implications(
+            2,
+            vec![Implication {
+                premise: bitvec![1, 0],
+                conclusion: bitvec![0, 1],
+            }],
+        );
+        assert_eq!(unrelated.num_closed_sets(), 3);
+    }
+}
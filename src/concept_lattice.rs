@@ -0,0 +1,824 @@
+use crate::FormalConcept;
+use crate::FormalContext;
+use crate::RawFormalConcept;
+
+use bitvec::prelude::*;
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+/// The concept lattice of a formal context.
+///
+/// Concepts are stored as [`RawFormalConcept`]s and addressed by index. The
+/// lattice also stores the cover relation (the edges of its Hasse diagram):
+/// [`ConceptLattice::upper_covers`] lists the concepts directly above a
+/// concept, and [`ConceptLattice::lower_covers`] the concepts directly below
+/// it.
+///
+/// Indices are assigned deterministically: [`FormalContext::concept_lattice`]
+/// sorts concepts by decreasing extent size (ties broken by intent), so index
+/// order is a linear extension of the dual lattice order. In particular, the
+/// top concept has index `0` and the bottom concept has the largest index.
+/// [`ConceptLattice::add_object`] and [`ConceptLattice::remove_object`] only
+/// renumber the concepts they touch and do not keep this order; call
+/// [`ConceptLattice::sort`] to restore it. [`ConceptLattice::top`] and
+/// [`ConceptLattice::bottom`] are correct either way.
+#[derive(Debug, Clone)]
+pub struct ConceptLattice<A = String, B = String> {
+    context: Arc<FormalContext<A, B>>,
+    concepts: Vec<RawFormalConcept>,
+    upper_covers: Vec<Vec<usize>>,
+    lower_covers: Vec<Vec<usize>>,
+    intent_index: HashMap<BitVec, usize>,
+    top: usize,
+    bottom: usize,
+}
+
+impl<A: Clone + Send + Sync, B: Clone + Send + Sync> FormalContext<A, B> {
+    /// Computes the concept lattice of this context, including its cover
+    /// relation.
+    ///
+    /// Concepts are enumerated with [`FormalContext::all_concepts_raw`], and
+    /// the upper covers of each concept are then computed directly with
+    /// Lindig's neighbour algorithm, which avoids comparing every pair of
+    /// concepts.
+    pub fn concept_lattice(&self) -> ConceptLattice<A, B> {
+        let mut concepts = self.all_concepts_raw();
+        sort_concepts(&mut concepts);
+        ConceptLattice::from_concepts(self.arc(), concepts)
+    }
+}
+
+// Sort concepts by decreasing extent size, breaking ties by intent. This is
+// the deterministic index order used by `ConceptLattice`.
+pub(crate) fn sort_concepts(concepts: &mut [RawFormalConcept]) {
+    concepts.sort_unstable_by(concept_order);
+}
+
+fn concept_order(a: &RawFormalConcept, b: &RawFormalConcept) -> Ordering {
+    b.extent
+        .count_ones()
+        .cmp(&a.extent.count_ones())
+        .then_with(|| a.intent.cmp(&b.intent))
+}
+
+fn intent_index(concepts: &[RawFormalConcept]) -> HashMap<BitVec, usize> {
+    concepts
+        .iter()
+        .enumerate()
+        .map(|(i, concept)| (concept.intent.clone(), i))
+        .collect()
+}
+
+impl<A: Send + Sync, B: Send + Sync> ConceptLattice<A, B> {
+    // Builds the lattice from every concept of `context`, computing covers.
+    fn from_concepts(context: Arc<FormalContext<A, B>>, concepts: Vec<RawFormalConcept>) -> Self {
+        let intent_index = intent_index(&concepts);
+        let upper_covers: Vec<Vec<usize>> = concepts
+            .par_iter()
+            .map(|concept| upper_neighbours(&context, concept, &intent_index))
+            .collect();
+        let mut lattice = Self {
+            context,
+            concepts,
+            upper_covers,
+            lower_covers: Vec::new(),
+            intent_index,
+            top: 0,
+            bottom: 0,
+        };
+        lattice.link();
+        lattice
+    }
+}
+
+// Incremental updates, in the style of AddIntent (van der Merwe, Obiedkov &
+// Kourie, 2004).
+//
+// Adding an object `g` with intent `D` changes the lattice as follows. Every
+// concept whose intent is a subset of `D` gains `g`. For every other
+// intersection `B ∩ D` of an intent `B` with `D` that is not yet an intent, a
+// new concept with intent `B ∩ D` is created. Its extent is that of its
+// generator, the concept with the smallest intent `B` giving the
+// intersection, plus `g`. The order between existing concepts does not
+// change, and the generator is the only existing concept that the new concept
+// covers, so only the upper covers of new concepts and of their generators
+// need to be recomputed.
+//
+// Removing an object undoes this: a concept `(A, B)` with `g` in `A` is
+// removed exactly when it covers its generator, the concept with extent
+// `A - g`; otherwise it only loses `g`.
+//
+// Neither update sorts the lattice. New concepts are appended, and the slot
+// of a removed concept is filled with the last concept, so only the cover
+// lists and intent index entries of the concepts involved are rewritten.
+
+impl<A: Clone + Send + Sync, B: Clone + Send + Sync> ConceptLattice<A, B> {
+    /// Appends an object with the given intent to the context, and updates
+    /// the lattice to match without recomputing it.
+    ///
+    /// Concepts generated by the new object are appended after the existing
+    /// ones, whose indices do not change, and the covers of the concepts
+    /// around them are updated; see [`ConceptLattice::sort`]. If the context is shared with [`FormalConcept`]s handed out earlier, it
+    /// is copied first, and those concepts keep referring to the old context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `intent.len()` differs from the number of attributes.
+    pub fn add_object(&mut self, label: A, intent: BitVec) {
+        let g = self.context.objects.len();
+        Arc::make_mut(&mut self.context).add_object(label, intent.clone());
+
+        // The generator of every intersection that is not an intent yet, with
+        // the size of its intent.
+        let mut generators: HashMap<BitVec, (usize, usize)> = HashMap::new();
+        for (i, concept) in self.concepts.iter_mut().enumerate() {
+            let meet = concept.intent.clone() & &intent;
+            concept.extent.push(meet == concept.intent);
+            if self.intent_index.contains_key(&meet) {
+                continue;
+            }
+            let size = concept.intent.count_ones();
+            generators
+                .entry(meet)
+                .and_modify(|generator| {
+                    if size < generator.1 {
+                        *generator = (i, size);
+                    }
+                })
+                .or_insert((i, size));
+        }
+        // Append the new concepts in the order of their generators, so that
+        // their indices do not depend on the hash map.
+        let mut generators: Vec<(BitVec, usize)> = generators
+            .into_iter()
+            .map(|(meet, (generator, _))| (meet, generator))
+            .collect();
+        generators.sort_unstable_by_key(|&(_, generator)| generator);
+        let mut affected = Vec::new();
+        for (meet, generator) in generators {
+            let mut extent = self.concepts[generator].extent.clone();
+            extent.set(g, true);
+            affected.push(generator);
+            affected.push(self.concepts.len());
+            self.intent_index.insert(meet.clone(), self.concepts.len());
+            self.concepts.push(RawFormalConcept {
+                extent,
+                intent: meet,
+            });
+            self.upper_covers.push(Vec::new());
+            self.lower_covers.push(Vec::new());
+        }
+        self.update_upper_covers(&affected);
+        self.find_top_and_bottom();
+    }
+
+    /// Removes the object with index `i` from the context, and updates the
+    /// lattice to match without recomputing it.
+    ///
+    /// Concepts that were only generated by the object are removed, and the
+    /// covers of the concepts below them are updated. Objects after `i` move
+    /// down by one. The slot of each removed concept is filled with the last
+    /// concept, so the indices of those concepts change, while all others
+    /// keep theirs; see [`ConceptLattice::sort`].
+    /// As with [`ConceptLattice::add_object`], a context shared with earlier
+    /// [`FormalConcept`]s is copied first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn remove_object(&mut self, i: usize) {
+        Arc::make_mut(&mut self.context).remove_objects_idx(&[i]);
+
+        let mut removed = Vec::new();
+        let mut affected = Vec::new();
+        for (k, concept) in self.concepts.iter().enumerate() {
+            if !concept.extent[i] {
+                continue;
+            }
+            let size = concept.extent.count_ones() - 1;
+            let generator = self.lower_covers[k].iter().copied().find(|&lower| {
+                let extent = &self.concepts[lower].extent;
+                !extent[i] && extent.count_ones() == size
+            });
+            if let Some(generator) = generator {
+                removed.push(k);
+                affected.push(generator);
+            }
+        }
+
+        for concept in &mut self.concepts {
+            concept.extent.remove(i);
+        }
+        for &k in &removed {
+            self.detach(k);
+        }
+        // From the highest slot down, so that a slot is never filled with a
+        // concept that is removed itself.
+        for &k in removed.iter().rev() {
+            let last = self.concepts.len() - 1;
+            self.swap_remove_concept(k);
+            for generator in &mut affected {
+                if *generator == last {
+                    *generator = k;
+                }
+            }
+        }
+        self.update_upper_covers(&affected);
+        self.find_top_and_bottom();
+    }
+
+    // Recompute the upper covers of the concepts in `indices` from the
+    // current context and intent index, and move them between the lower
+    // covers of their old and new upper covers.
+    fn update_upper_covers(&mut self, indices: &[usize]) {
+        let updated: Vec<Vec<usize>> = indices
+            .par_iter()
+            .map(|&i| upper_neighbours(&self.context, &self.concepts[i], &self.intent_index))
+            .collect();
+        for (&i, uppers) in indices.iter().zip(updated) {
+            for &upper in &self.upper_covers[i] {
+                self.lower_covers[upper].retain(|&lower| lower != i);
+            }
+            for &upper in &uppers {
+                self.lower_covers[upper].push(i);
+            }
+            self.upper_covers[i] = uppers;
+        }
+    }
+}
+
+impl<A, B> ConceptLattice<A, B> {
+    // Derive the lower covers, top and bottom from the upper covers.
+    fn link(&mut self) {
+        self.lower_covers = vec![Vec::new(); self.concepts.len()];
+        for (i, uppers) in self.upper_covers.iter().enumerate() {
+            for &j in uppers {
+                self.lower_covers[j].push(i);
+            }
+        }
+        self.find_top_and_bottom();
+    }
+
+    // Find the top and bottom by walking the covers from any concept; every
+    // path ends at them.
+    fn find_top_and_bottom(&mut self) {
+        let mut top = 0;
+        while let Some(&upper) = self.upper_covers[top].first() {
+            top = upper;
+        }
+        let mut bottom = 0;
+        while let Some(&lower) = self.lower_covers[bottom].first() {
+            bottom = lower;
+        }
+        self.top = top;
+        self.bottom = bottom;
+    }
+
+    // Unlink concept `k` from its covers and the intent index.
+    fn detach(&mut self, k: usize) {
+        for upper in std::mem::take(&mut self.upper_covers[k]) {
+            self.lower_covers[upper].retain(|&lower| lower != k);
+        }
+        for lower in std::mem::take(&mut self.lower_covers[k]) {
+            self.upper_covers[lower].retain(|&upper| upper != k);
+        }
+        self.intent_index.remove(&self.concepts[k].intent);
+    }
+
+    // Remove the detached concept `k` by moving the last concept into its
+    // slot, and renumber the references to the moved concept.
+    fn swap_remove_concept(&mut self, k: usize) {
+        let last = self.concepts.len() - 1;
+        self.concepts.swap_remove(k);
+        self.upper_covers.swap_remove(k);
+        self.lower_covers.swap_remove(k);
+        if k == last {
+            return;
+        }
+        for &upper in &self.upper_covers[k] {
+            for lower in &mut self.lower_covers[upper] {
+                if *lower == last {
+                    *lower = k;
+                }
+            }
+        }
+        for &lower in &self.lower_covers[k] {
+            for upper in &mut self.upper_covers[lower] {
+                if *upper == last {
+                    *upper = k;
+                }
+            }
+        }
+        self.intent_index.insert(self.concepts[k].intent.clone(), k);
+    }
+
+    /// Renumbers the concepts into the order of
+    /// [`FormalContext::concept_lattice`]: by decreasing extent size, with
+    /// ties broken by intent.
+    ///
+    /// Only needed after [`ConceptLattice::add_object`] or
+    /// [`ConceptLattice::remove_object`], which leave the order alone so that
+    /// a stream of updates does not re-sort the lattice each time. Concept
+    /// indices obtained before the call may change.
+    pub fn sort(&mut self) {
+        let mut entries: Vec<(usize, RawFormalConcept, Vec<usize>)> = self
+            .concepts
+            .drain(..)
+            .zip(self.upper_covers.drain(..))
+            .enumerate()
+            .map(|(i, (concept, uppers))| (i, concept, uppers))
+            .collect();
+        entries.sort_unstable_by(|a, b| concept_order(&a.1, &b.1));
+        let mut position = vec![0; entries.len()];
+        for (new, (old, _, _)) in entries.iter().enumerate() {
+            position[*old] = new;
+        }
+        for (_, concept, mut uppers) in entries {
+            for upper in &mut uppers {
+                *upper = position[*upper];
+            }
+            self.concepts.push(concept);
+            self.upper_covers.push(uppers);
+        }
+        for (i, concept) in self.concepts.iter().enumerate() {
+            *self
+                .intent_index
+                .get_mut(&concept.intent)
+                .expect("Every intent of the lattice is indexed") = i;
+        }
+        self.link();
+    }
+}
+
+impl<A, B> ConceptLattice<A, B> {
+    /// Returns the context this lattice was computed from.
+    pub fn context(&self) -> &Arc<FormalContext<A, B>> {
+        &self.context
+    }
+
+    /// Returns the number of concepts in the lattice.
+    pub fn num_concepts(&self) -> usize {
+        self.concepts.len()
+    }
+
+    /// Returns all concepts, indexed consistently with the other methods.
+    pub fn concepts(&self) -> &[RawFormalConcept] {
+        &self.concepts
+    }
+
+    /// Returns the raw concept with index `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn raw_concept(&self, i: usize) -> &RawFormalConcept {
+        &self.concepts[i]
+    }
+
+    /// Returns the concept with index `i` as a named [`FormalConcept`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn concept(&self, i: usize) -> FormalConcept<A, B> {
+        FormalConcept {
+            context: self.context.clone(),
+            data: self.concepts[i].clone(),
+        }
+    }
+
+    /// Returns the index of the concept with the given intent, if the intent
+    /// is closed.
+    pub fn index_of_intent(&self, intent: &BitVec) -> Option<usize> {
+        self.intent_index.get(intent).copied()
+    }
+
+    /// Returns the indices of the upper covers of concept `i`, i.e. the
+    /// concepts directly above it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn upper_covers(&self, i: usize) -> &[usize] {
+        &self.upper_covers[i]
+    }
+
+    /// Returns the indices of the lower covers of concept `i`, i.e. the
+    /// concepts directly below it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn lower_covers(&self, i: usize) -> &[usize] {
+        &self.lower_covers[i]
+    }
+
+    /// Returns the index of the top concept, whose extent contains every
+    /// object.
+    pub fn top(&self) -> usize {
+        self.top
+    }
+
+    /// Returns the index of the bottom concept, whose intent contains every
+    /// attribute.
+    pub fn bottom(&self) -> usize {
+        self.bottom
+    }
+
+    /// Iterates over the cover relation as `(lower, upper)` index pairs.
+    pub fn covers_iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.upper_covers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, uppers)| uppers.iter().map(move |&j| (i, j)))
+    }
+
+    /// Returns the index of the object concept of object `i`, the smallest
+    /// concept whose extent contains the object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn object_concept(&self, i: usize) -> usize {
+        self.index_of_intent(self.context.get_object_intent(i))
+            .expect("Object intents are closed")
+    }
+
+    /// Returns the index of the attribute concept of attribute `j`, the
+    /// largest concept whose intent contains the attribute.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `j` is out of bounds.
+    pub fn attribute_concept(&self, j: usize) -> usize {
+        let intent = self.context.induce_r(self.context.get_attribute_extent(j));
+        self.index_of_intent(&intent)
+            .expect("Attribute concept intents are closed")
+    }
+
+    // For each concept, the objects and attributes it introduces, i.e. the
+    // labels shown at that concept in a reduced labelling.
+    pub(crate) fn reduced_labels(&self) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+        let mut objects = vec![Vec::new(); self.concepts.len()];
+        let mut attributes = vec![Vec::new(); self.concepts.len()];
+        for i in 0..self.context.objects.len() {
+            objects[self.object_concept(i)].push(i);
+        }
+        for j in 0..self.context.attributes.len() {
+            attributes[self.attribute_concept(j)].push(j);
+        }
+        (objects, attributes)
+    }
+}
+
+/// Which labels to attach to the nodes of a lattice diagram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LatticeLabels {
+    /// Reduced labelling: each attribute label appears only at its attribute
+    /// concept, and each object label only at its object concept. This is the
+    /// usual way to draw concept lattices, since the full extent and intent of
+    /// a concept can be read off the diagram.
+    #[default]
+    Reduced,
+    /// The full extent and intent of every concept.
+    Full,
+    /// Only the sizes of the extent and intent of every concept.
+    Sizes,
+}
+
+impl<A: Display, B: Display> ConceptLattice<A, B> {
+    /// Writes the Hasse diagram of this lattice in Graphviz DOT format.
+    ///
+    /// Nodes are named by concept index and edges point from each concept to
+    /// its upper covers; with `rankdir=BT`, the top concept is drawn at the
+    /// top. Node labels list attributes on the first line and objects on the
+    /// second, as selected by `labels`. The output can be rendered with e.g.
+    /// `dot -Tsvg`.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error produced by `output`.
+    pub fn write_dot(&self, mut output: impl Write, labels: LatticeLabels) -> io::Result<()> {
+        writeln!(output, "digraph lattice {{")?;
+        writeln!(output, "    rankdir=BT;")?;
+        writeln!(output, "    node [shape=box];")?;
+        writeln!(output, "    edge [arrowhead=none];")?;
+        for (i, (attributes, objects)) in self.node_labels(labels).into_iter().enumerate() {
+            writeln!(
+                output,
+                "    {i} [label=\"{}\\n{}\"];",
+                escape_dot(&attributes),
+                escape_dot(&objects)
+            )?;
+        }
+        for (lower, upper) in self.covers_iter() {
+            writeln!(output, "    {lower} -> {upper};")?;
+        }
+        writeln!(output, "}}")?;
+        output.flush()
+    }
+
+    // The attribute and object label text of each concept, as selected by
+    // `labels`.
+    pub(crate) fn node_labels(&self, labels: LatticeLabels) -> Vec<(String, String)> {
+        let (introduced_objects, introduced_attributes) = match labels {
+            LatticeLabels::Reduced => self.reduced_labels(),
+            _ => (Vec::new(), Vec::new()),
+        };
+
+        self.concepts
+            .iter()
+            .enumerate()
+            .map(|(i, concept)| match labels {
+                LatticeLabels::Reduced => (
+                    join_labels(
+                        &self.context.attributes,
+                        introduced_attributes[i].iter().copied(),
+                    ),
+                    join_labels(&self.context.objects, introduced_objects[i].iter().copied()),
+                ),
+                LatticeLabels::Full => (
+                    join_labels(&self.context.attributes, concept.intent.iter_ones()),
+                    join_labels(&self.context.objects, concept.extent.iter_ones()),
+                ),
+                LatticeLabels::Sizes => (
+                    format!("intent: {}", concept.intent.count_ones()),
+                    format!("extent: {}", concept.extent.count_ones()),
+                ),
+            })
+            .collect()
+    }
+}
+
+// Comma-separate the labels at the given indices.
+pub(crate) fn join_labels<T: Display>(
+    labels: &[T],
+    indices: impl Iterator<Item = usize>,
+) -> String {
+    indices
+        .map(|i| labels[i].to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Escape a string for use inside a double-quoted DOT identifier.
+fn escape_dot(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for ch in label.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+// Lindig's upper neighbour computation.
+//
+// Every upper neighbour of `(A, B)` has the form `((A + g)'', (A + g)')` for
+// some object `g` outside `A`. Candidates are generated object by object; `min`
+// tracks the objects that may still generate a new neighbour. A candidate is a
+// neighbour exactly when its extent adds no object from `min` other than `g`
+// itself, and otherwise `g` generates a concept that is not minimal, so it is
+// dropped from `min`. Each neighbour is reported once.
+//
+// Upper neighbours have larger extents, so `intent_index` only needs to cover
+// the concepts above `concept`; this also works on iceberg lattices.
+pub(crate) fn upper_neighbours<A, B>(
+    context: &FormalContext<A, B>,
+    concept: &RawFormalConcept,
+    intent_index: &HashMap<BitVec, usize>,
+) -> Vec<usize> {
+    let mut min = !concept.extent.clone();
+    let mut neighbours = Vec::new();
+    for g in (!concept.extent.clone()).iter_ones() {
+        let intent = concept.intent.clone() & context.get_object_intent(g);
+        let mut extent = context.induce_l(&intent);
+        extent &= !concept.extent.clone();
+        extent.set(g, false);
+        if (extent & &min).not_any() {
+            neighbours.push(
+                *intent_index
+                    .get(&intent)
+                    .expect("Upper neighbour must be a concept of the lattice"),
+            );
+        } else {
+            min.set(g, false);
+        }
+    }
+    neighbours
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::lives_in_water;
+
+    #[test]
+    fn test_covers_match_pairwise_definition() {
+        let lattice = lives_in_water().concept_lattice();
+        let concepts = lattice.concepts();
+        assert_eq!(lattice.num_concepts(), 19);
+
+        for i in 0..concepts.len() {
+            let mut expected: Vec<usize> = (0..concepts.len())
+                .filter(|&j| {
+                    concepts[i] < concepts[j]
+                        && !(0..concepts.len())
+                            .any(|k| concepts[i] < concepts[k] && concepts[k] < concepts[j])
+                })
+                .collect();
+            let mut actual = lattice.upper_covers(i).to_vec();
+            expected.sort_unstable();
+            actual.sort_unstable();
+            assert_eq!(actual, expected);
+        }
+        for (lower, upper) in lattice.covers_iter() {
+            assert!(lattice.lower_covers(upper).contains(&lower));
+        }
+    }
+
+    #[test]
+    fn test_top_and_bottom() {
+        let lattice = lives_in_water().concept_lattice();
+
+        assert_eq!(lattice.top(), 0);
+        assert_eq!(lattice.bottom(), lattice.num_concepts() - 1);
+        assert!(lattice.raw_concept(lattice.top()).extent.all());
+        assert!(lattice.raw_concept(lattice.bottom()).intent.all());
+        assert!(lattice.upper_covers(lattice.top()).is_empty());
+        assert!(lattice.lower_covers(lattice.bottom()).is_empty());
+        assert!(lattice.concept(lattice.top()).validate());
+
+        let bottom_intent = lattice.raw_concept(lattice.bottom()).intent.clone();
+        assert_eq!(
+            lattice.index_of_intent(&bottom_intent),
+            Some(lattice.bottom())
+        );
+    }
+
+    #[test]
+    fn test_single_concept_lattice() {
+        let context = FormalContext::<usize, usize>::zero_context(vec![], vec![]);
+        let lattice = context.concept_lattice();
+
+        assert_eq!(lattice.num_concepts(), 1);
+        assert_eq!(lattice.top(), lattice.bottom());
+        assert_eq!(lattice.covers_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_object_and_attribute_concepts() {
+        let context = lives_in_water();
+        let lattice = context.concept_lattice();
+
+        for i in 0..context.objects.len() {
+            let concept = lattice.raw_concept(lattice.object_concept(i));
+            assert!(concept.extent[i]);
+            assert_eq!(&concept.intent, context.get_object_intent(i));
+        }
+        for j in 0..context.attributes.len() {
+            let concept = lattice.raw_concept(lattice.attribute_concept(j));
+            assert!(concept.intent[j]);
+            assert_eq!(&concept.extent, context.get_attribute_extent(j));
+        }
+    }
+
+    #[test]
+    fn test_write_dot() {
+        let context = FormalContext::new(
+            vec!["x", "y \"quoted\""],
+            vec!["a", "b"],
+            vec![bitvec![1, 0], bitvec![1, 1]],
+        );
+        let lattice = context.concept_lattice();
+
+        let mut buffer = Vec::new();
+        lattice
+            .write_dot(&mut buffer, LatticeLabels::Reduced)
+            .unwrap();
+        let expected = concat!(
+            "digraph lattice {\n",
+            "    rankdir=BT;\n",
+            "    node [shape=box];\n",
+            "    edge [arrowhead=none];\n",
+            "    0 [label=\"a\\nx\"];\n",
+            "    1 [label=\"b\\ny \\\"quoted\\\"\"];\n",
+            "    1 -> 0;\n",
+            "}\n",
+        );
+        assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+
+        let mut buffer = Vec::new();
+        lattice
+            .write_dot(&mut buffer, LatticeLabels::Sizes)
+            .unwrap();
+        assert!(
+            String::from_utf8(buffer)
+                .unwrap()
+                .contains("    0 [label=\"intent: 1\\nextent: 2\"];\n")
+        );
+    }
+
+    // Check that `lattice` is exactly the lattice of its context, both as
+    // left by the incremental updates and once sorted.
+    fn assert_matches_fresh(lattice: &ConceptLattice<usize, usize>) {
+        let fresh = lattice.context().concept_lattice();
+        for (i, concept) in lattice.concepts().iter().enumerate() {
+            assert_eq!(lattice.index_of_intent(&concept.intent), Some(i));
+            for &upper in lattice.upper_covers(i) {
+                assert!(lattice.lower_covers(upper).contains(&i));
+            }
+            for &lower in lattice.lower_covers(i) {
+                assert!(lattice.upper_covers(lower).contains(&i));
+            }
+        }
+        assert_eq!(
+            lattice.raw_concept(lattice.top()),
+            fresh.raw_concept(fresh.top())
+        );
+        assert_eq!(
+            lattice.raw_concept(lattice.bottom()),
+            fresh.raw_concept(fresh.bottom())
+        );
+
+        let mut lattice = lattice.clone();
+        lattice.sort();
+        assert_eq!(lattice.concepts(), fresh.concepts());
+        for i in 0..fresh.num_concepts() {
+            let mut uppers = lattice.upper_covers(i).to_vec();
+            let mut lowers = lattice.lower_covers(i).to_vec();
+            uppers.sort_unstable();
+            lowers.sort_unstable();
+            let mut expected_uppers = fresh.upper_covers(i).to_vec();
+            let mut expected_lowers = fresh.lower_covers(i).to_vec();
+            expected_uppers.sort_unstable();
+            expected_lowers.sort_unstable();
+            assert_eq!(uppers, expected_uppers);
+            assert_eq!(lowers, expected_lowers);
+            assert_eq!(
+                lattice.index_of_intent(&fresh.raw_concept(i).intent),
+                Some(i)
+            );
+        }
+        assert_eq!(lattice.top(), fresh.top());
+        assert_eq!(lattice.bottom(), fresh.bottom());
+    }
+
+    #[test]
+    fn test_incremental_updates_match_fresh_lattice() {
+        let mut state: u64 = 7;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            state >> 33
+        };
+        for attributes in [0, 1, 6, 9] {
+            let context =
+                FormalContext::<usize, usize>::zero_context(vec![], (0..attributes).collect());
+            let mut lattice = context.concept_lattice();
+            for label in 0..30 {
+                let intent = (0..attributes).map(|_| next() % 3 != 0).collect();
+                lattice.add_object(label, intent);
+                assert_matches_fresh(&lattice);
+                if next() % 4 == 0 {
+                    let objects = lattice.context().objects.len();
+                    lattice.remove_object(next() as usize % objects);
+                    assert_matches_fresh(&lattice);
+                }
+            }
+            while !lattice.context().objects.is_empty() {
+                lattice.remove_object(0);
+                assert_matches_fresh(&lattice);
+            }
+        }
+    }
+
+    #[test]
+    fn test_incremental_duplicate_objects() {
+        let mut lattice = lives_in_water().concept_lattice();
+        let frog = lattice.context().get_object_intent(2).clone();
+
+        lattice.add_object("tree frog", frog);
+        assert_eq!(lattice.num_concepts(), 19);
+        let concept = lattice.raw_concept(lattice.object_concept(8));
+        assert_eq!(concept.extent.count_ones(), 2);
+
+        lattice.remove_object(2);
+        assert_eq!(lattice.num_concepts(), 19);
+        assert_eq!(lattice.context().objects[7], "tree frog");
+        lattice.sort();
+        assert_eq!(
+            lattice.concepts(),
+            lattice.context().concept_lattice().concepts()
+        );
+    }
+}
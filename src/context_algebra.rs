@@ -0,0 +1,318 @@
+use crate::ContextError;
+use crate::FormalContext;
+
+use bitvec::prelude::*;
+
+// The constructions follow Ganter & Wille, "Formal Concept Analysis",
+// Section 1.5. Products and sums lay out the combined objects (and
+// attributes) of the first context before those of the second; pairs are
+// ordered with the first component varying slowest.
+
+impl<A: Clone, B: Clone> FormalContext<A, B> {
+    /// Returns the dual context, in which objects and attributes swap roles.
+    ///
+    /// This is [`FormalContext::transpose`] on a copy of this context.
+    pub fn dual(&self) -> FormalContext<B, A> {
+        self.clone().transpose()
+    }
+
+    /// Returns the complementary context, in which an object has exactly the
+    /// attributes it does not have in this context.
+    pub fn complement(&self) -> Self {
+        let relation = (0..self.objects.len())
+            .map(|i| !self.get_object_intent(i).clone())
+            .collect();
+        FormalContext::new(self.objects.clone(), self.attributes.clone(), relation)
+    }
+
+    /// Returns the direct product of this context and `other`.
+    ///
+    /// The objects and attributes are all pairs of objects and attributes,
+    /// respectively. The object `(g, h)` has the attribute `(m, n)` when `g`
+    /// has `m` in this context or `h` has `n` in `other`. Pairs of labels are
+    /// always distinct, so no labels can clash.
+    pub fn direct_product<C: Clone, D: Clone>(
+        &self,
+        other: &FormalContext<C, D>,
+    ) -> FormalContext<(A, C), (B, D)> {
+        let objects = pairs(&self.objects, &other.objects);
+        let attributes = pairs(&self.attributes, &other.attributes);
+        let full = BitVec::repeat(true, other.attributes.len());
+        let mut relation = Vec::with_capacity(objects.len());
+        for i in 0..self.objects.len() {
+            for k in 0..other.objects.len() {
+                let mut intent = BitVec::with_capacity(attributes.len());
+                for has in self.get_object_intent(i).iter().by_vals() {
+                    intent.extend_from_bitslice(if has {
+                        &full
+                    } else {
+                        other.get_object_intent(k)
+                    });
+                }
+                relation.push(intent);
+            }
+        }
+        FormalContext::new(objects, attributes, relation)
+    }
+}
+
+impl<A: Clone + PartialEq, B: Clone + PartialEq> FormalContext<A, B> {
+    /// Returns the apposition of this context and `other`: the context on the
+    /// common objects with the attributes of this context followed by those
+    /// of `other`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextError::ObjectMismatch`] if the objects of the two
+    /// contexts differ, and [`ContextError::DuplicateAttribute`] if they have
+    /// an attribute in common.
+    pub fn apposition(&self, other: &Self) -> Result<Self, ContextError> {
+        if self.objects != other.objects {
+            return Err(ContextError::ObjectMismatch);
+        }
+        check_disjoint(&self.attributes, &other.attributes)
+            .map_err(ContextError::DuplicateAttribute)?;
+        let relation = (0..self.objects.len())
+            .map(|i| concat(self.get_object_intent(i), other.get_object_intent(i)))
+            .collect();
+        Ok(FormalContext::new(
+            self.objects.clone(),
+            concat_labels(&self.attributes, &other.attributes),
+            relation,
+        ))
+    }
+
+    /// Returns the subposition of this context and `other`: the context on
+    /// the common attributes with the objects of this context followed by
+    /// those of `other`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextError::AttributeMismatch`] if the attributes of the
+    /// two contexts differ, and [`ContextError::DuplicateObject`] if they
+    /// have an object in common.
+    pub fn subposition(&self, other: &Self) -> Result<Self, ContextError> {
+        if self.attributes != other.attributes {
+            return Err(ContextError::AttributeMismatch);
+        }
+        check_disjoint(&self.objects, &other.objects).map_err(ContextError::DuplicateObject)?;
+        let relation = rows(self).chain(rows(other)).collect();
+        Ok(FormalContext::new(
+            concat_labels(&self.objects, &other.objects),
+            self.attributes.clone(),
+            relation,
+        ))
+    }
+
+    /// Returns the direct sum of this context and `other`.
+    ///
+    /// The objects and attributes are those of this context followed by
+    /// those of `other`. Within each context the relation is unchanged, and
+    /// every object of one context has every attribute of the other. The
+    /// concept lattice of the direct sum is the product of the two concept
+    /// lattices.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextError::DuplicateObject`] or
+    /// [`ContextError::DuplicateAttribute`] if the contexts have an object or
+    /// an attribute in common.
+    pub fn direct_sum(&self, other: &Self) -> Result<Self, ContextError> {
+        check_disjoint(&self.objects, &other.objects).map_err(ContextError::DuplicateObject)?;
+        check_disjoint(&self.attributes, &other.attributes)
+            .map_err(ContextError::DuplicateAttribute)?;
+        let full_self = BitVec::repeat(true, self.attributes.len());
+        let full_other = BitVec::repeat(true, other.attributes.len());
+        let relation = rows(self)
+            .map(|intent| concat(&intent, &full_other))
+            .chain(rows(other).map(|intent| concat(&full_self, &intent)))
+            .collect();
+        Ok(FormalContext::new(
+            concat_labels(&self.objects, &other.objects),
+            concat_labels(&self.attributes, &other.attributes),
+            relation,
+        ))
+    }
+
+    /// Returns the semiproduct of this context and `other`.
+    ///
+    /// The objects are all pairs of objects, and the attributes are those of
+    /// this context followed by those of `other`. The object `(g, h)` has an
+    /// attribute of this context when `g` has it, and an attribute of `other`
+    /// when `h` has it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContextError::DuplicateAttribute`] if the contexts have an
+    /// attribute in common.
+    pub fn semiproduct<C: Clone>(
+        &self,
+        other: &FormalContext<C, B>,
+    ) -> Result<FormalContext<(A, C), B>, ContextError> {
+        check_disjoint(&self.attributes, &other.attributes)
+            .map_err(ContextError::DuplicateAttribute)?;
+        let mut relation = Vec::with_capacity(self.objects.len() * other.objects.len());
+        for i in 0..self.objects.len() {
+            for k in 0..other.objects.len() {
+                relation.push(concat(
+                    self.get_object_intent(i),
+                    other.get_object_intent(k),
+                ));
+            }
+        }
+        Ok(FormalContext::new(
+            pairs(&self.objects, &other.objects),
+            concat_labels(&self.attributes, &other.attributes),
+            relation,
+        ))
+    }
+}
+
+// The object intents of `context`, copied.
+fn rows<A, B>(context: &FormalContext<A, B>) -> impl Iterator<Item = BitVec> + '_ {
+    (0..context.objects.len()).map(|i| context.get_object_intent(i).clone())
+}
+
+fn concat(left: &BitVec, right: &BitVec) -> BitVec {
+    let mut joined = BitVec::with_capacity(left.len() + right.len());
+    joined.extend_from_bitslice(left);
+    joined.extend_from_bitslice(right);
+    joined
+}
+
+fn concat_labels<T: Clone>(left: &[T], right: &[T]) -> Vec<T> {
+    left.iter().chain(right).cloned().collect()
+}
+
+fn pairs<T: Clone, U: Clone>(left: &[T], right: &[U]) -> Vec<(T, U)> {
+    left.iter()
+        .flat_map(|t| right.iter().map(move |u| (t.clone(), u.clone())))
+        .collect()
+}
+
+// Fail with the index of the first label of `right` that also occurs in
+// `left`.
+fn check_disjoint<T: PartialEq>(left: &[T], right: &[T]) -> Result<(), usize> {
+    match right.iter().position(|label| left.contains(label)) {
+        Some(index) => Err(index),
+        None => Ok(()),
+    }
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain() -> FormalContext<&'static str, &'static str> {
+        FormalContext::new(
+            vec!["g1", "g2"],
+            vec!["m1", "m2"],
+            vec![bitvec![1, 0], bitvec![1, 1]],
+        )
+    }
+
+    fn nominal() -> FormalContext<&'static str, &'static str> {
+        FormalContext::new(
+            vec!["h1", "h2"],
+            vec!["n1", "n2"],
+            vec![bitvec![1, 0], bitvec![0, 1]],
+        )
+    }
+
+    #[test]
+    fn test_dual_and_complement() {
+        let context = chain();
+
+        let dual = context.dual();
+        assert_eq!(dual.objects, context.attributes);
+        assert_eq!(dual.get_object_intent(0), &bitvec![1, 1]);
+        assert_eq!(dual.get_object_intent(1), &bitvec![0, 1]);
+        assert!(dual.validate());
+        assert_eq!(dual.num_concepts(), context.num_concepts());
+        assert_eq!(dual.transpose(), context);
+
+        let complement = context.complement();
+        assert_eq!(complement.get_object_intent(0), &bitvec![0, 1]);
+        assert_eq!(complement.get_object_intent(1), &bitvec![0, 0]);
+        assert!(complement.validate());
+        assert_eq!(complement.complement(), context);
+    }
+
+    #[test]
+    fn test_apposition_and_subposition() {
+        let context = chain();
+        let other = FormalContext::new(vec!["g1", "g2"], vec!["m3"], vec![bitvec![0], bitvec![1]]);
+
+        let apposition = context.apposition(&other).unwrap();
+        assert_eq!(apposition.attributes, ["m1", "m2", "m3"]);
+        assert_eq!(apposition.get_object_intent(0), &bitvec![1, 0, 0]);
+        assert_eq!(apposition.get_object_intent(1), &bitvec![1, 1, 1]);
+        assert!(apposition.validate());
+
+        let subposition = context.dual().subposition(&other.dual()).unwrap();
+        assert_eq!(subposition, apposition.dual());
+
+        assert_eq!(
+            context.apposition(&context),
+            Err(ContextError::DuplicateAttribute(0))
+        );
+        assert_eq!(
+            context.apposition(&nominal()),
+            Err(ContextError::ObjectMismatch)
+        );
+        assert_eq!(
+            context.subposition(&nominal()),
+            Err(ContextError::AttributeMismatch)
+        );
+        assert_eq!(
+            context.subposition(&context),
+            Err(ContextError::DuplicateObject(0))
+        );
+    }
+
+    #[test]
+    fn test_direct_sum_is_lattice_product() {
+        let context = chain();
+        let other = nominal();
+
+        let sum = context.direct_sum(&other).unwrap();
+
+        assert_eq!(sum.objects, ["g1", "g2", "h1", "h2"]);
+        assert_eq!(sum.get_object_intent(0), &bitvec![1, 0, 1, 1]);
+        assert_eq!(sum.get_object_intent(3), &bitvec![1, 1, 0, 1]);
+        assert!(sum.validate());
+        assert_eq!(
+            sum.num_concepts(),
+            context.num_concepts() * other.num_concepts()
+        );
+        assert_eq!(
+            context.direct_sum(&context),
+            Err(ContextError::DuplicateObject(0))
+        );
+    }
+
+    #[test]
+    fn test_products() {
+        let context = chain();
+        let other = nominal();
+
+        let product = context.direct_product(&other);
+        assert_eq!(product.objects.len(), 4);
+        assert_eq!(product.attributes[1], ("m1", "n2"));
+        // (g1, h2) has (m, n) when g1 has m or h2 has n.
+        let i = product.objects.iter().position(|&o| o == ("g1", "h2"));
+        assert_eq!(product.get_object_intent(i.unwrap()), &bitvec![1, 1, 0, 1]);
+        assert!(product.validate());
+
+        let semiproduct = context.semiproduct(&other).unwrap();
+        assert_eq!(semiproduct.attributes, ["m1", "m2", "n1", "n2"]);
+        assert_eq!(semiproduct.objects[1], ("g1", "h2"));
+        assert_eq!(semiproduct.get_object_intent(1), &bitvec![1, 0, 0, 1]);
+        assert!(semiproduct.validate());
+        assert_eq!(
+            context.semiproduct(&context),
+            Err(ContextError::DuplicateAttribute(0))
+        );
+    }
+}
@@ -0,0 +1,402 @@
+use crate::FormalContext;
+use crate::{ParseError, ParseErrorKind};
+
+use bitvec::prelude::*;
+use std::fmt::Display;
+use std::io::{self, Read, Write};
+
+/// Options for reading and writing contexts as CSV cross tables; see
+/// [`FormalContext::try_from_csv`] and [`FormalContext::write_csv`].
+///
+/// The default options read comma-separated files with `"` quoting, as
+/// exported by most spreadsheets. [`CsvOptions::tsv`] returns the options for
+/// tab-separated files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// The field separator.
+    pub delimiter: char,
+    /// The character used to quote fields that contain the delimiter, the
+    /// quote itself or a line break, or `None` to disable quoting. Inside a
+    /// quoted field, the quote character is escaped by doubling it.
+    pub quote: Option<char>,
+    /// Cell contents meaning that the object has the attribute. Cells are
+    /// compared case-insensitively after trimming whitespace. The first
+    /// marker is used when writing.
+    pub true_markers: Vec<String>,
+    /// Cell contents meaning that the object does not have the attribute,
+    /// compared like `true_markers`. The first marker is used when writing.
+    pub false_markers: Vec<String>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: Some('"'),
+            true_markers: vec!["1".into(), "X".into(), "true".into()],
+            false_markers: vec!["0".into(), ".".into(), "false".into(), "".into()],
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Returns the default options with a tab as the delimiter.
+    pub fn tsv() -> Self {
+        Self {
+            delimiter: '\t',
+            ..Self::default()
+        }
+    }
+
+    // Whether `cell` is a true or false marker, if it is either.
+    fn parse_cell(&self, cell: &str) -> Option<bool> {
+        let cell = cell.trim();
+        let matches = |markers: &[String]| {
+            markers
+                .iter()
+                .any(|marker| marker.trim().eq_ignore_ascii_case(cell))
+        };
+        if matches(&self.true_markers) {
+            Some(true)
+        } else if matches(&self.false_markers) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+impl FormalContext {
+    /// Loads a formal context from a CSV cross table.
+    ///
+    /// This is the panicking counterpart of [`FormalContext::try_from_csv`];
+    /// see there for the expected format.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the input cannot be read or is malformed.
+    pub fn from_csv(input: impl Read, options: &CsvOptions) -> Self {
+        Self::try_from_csv(input, options).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Loads a formal context from a CSV cross table.
+    ///
+    /// The first record is the header: its first field is ignored, and the
+    /// remaining fields are the attribute names. Every further record starts
+    /// with an object name, followed by one cell per attribute containing one
+    /// of the markers of `options`. Empty lines are skipped, and both `\n`
+    /// and `\r\n` line endings are accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseError`] if the input cannot be read, has no header,
+    /// has a record with the wrong number of fields, contains a cell that is
+    /// not a marker, ends inside a quoted field, or has characters between a
+    /// closing quote and the next delimiter.
+    pub fn try_from_csv(mut input: impl Read, options: &CsvOptions) -> Result<Self, ParseError> {
+        let mut text = String::new();
+        input
+            .read_to_string(&mut text)
+            .map_err(|err| ParseError::io(1, err))?;
+
+        let mut records = csv_records(&text, options)?.into_iter();
+        let header = records
+            .next()
+            .ok_or_else(|| ParseError::new(ParseErrorKind::BadHeader, 1, 1))?;
+        let attributes: Vec<String> = header.into_iter().skip(1).map(|f| f.text).collect();
+
+        let mut objects = Vec::new();
+        let mut relation = Vec::new();
+        for record in records {
+            if record.len() != attributes.len() + 1 {
+                let field = &record[record.len().min(attributes.len() + 1) - 1];
+                return Err(ParseError::new(
+                    ParseErrorKind::DimensionMismatch,
+                    field.line,
+                    field.column,
+                ));
+            }
+            let mut fields = record.into_iter();
+            objects.push(fields.next().expect("Records are never empty").text);
+            let mut row = BitVec::with_capacity(attributes.len());
+            for field in fields {
+                let cell = options.parse_cell(&field.text).ok_or_else(|| {
+                    ParseError::new(ParseErrorKind::InvalidToken, field.line, field.column)
+                        .with_token(field.text.as_str())
+                })?;
+                row.push(cell);
+            }
+            relation.push(row);
+        }
+        Ok(Self::new(objects, attributes, relation))
+    }
+}
+
+impl<A: Display, B: Display> FormalContext<A, B> {
+    /// Writes this context as a CSV cross table, in the format read by
+    /// [`FormalContext::try_from_csv`].
+    ///
+    /// The header starts with an empty field, and cells use the first true
+    /// and false markers of `options`. Labels are quoted when they contain
+    /// the delimiter, the quote character or a line break, or have leading
+    /// or trailing whitespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error produced by `output`, or an error of kind
+    /// [`io::ErrorKind::InvalidInput`] if `options` has no true or false
+    /// marker, or a label needs quoting but quoting is disabled.
+    pub fn write_csv(&self, mut output: impl Write, options: &CsvOptions) -> io::Result<()> {
+        let (Some(yes), Some(no)) = (options.true_markers.first(), options.false_markers.first())
+        else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "CSV options need a true and a false marker",
+            ));
+        };
+        let delimiter = options.delimiter;
+
+        write!(output, "{}", csv_field("", options)?)?;
+        for attribute in &self.attributes {
+            write!(
+                output,
+                "{delimiter}{}",
+                csv_field(&attribute.to_string(), options)?
+            )?;
+        }
+        writeln!(output)?;
+        for (i, object) in self.objects.iter().enumerate() {
+            write!(output, "{}", csv_field(&object.to_string(), options)?)?;
+            for j in 0..self.attributes.len() {
+                let marker = if self.get_relation_idx(i, j) { yes } else { no };
+                write!(output, "{delimiter}{}", csv_field(marker, options)?)?;
+            }
+            writeln!(output)?;
+        }
+        output.flush()
+    }
+}
+
+// Quote `text` for output if needed.
+fn csv_field(text: &str, options: &CsvOptions) -> io::Result<String> {
+    let needs_quoting = text.contains(options.delimiter)
+        || text.contains(['\n', '\r'])
+        || options.quote.is_some_and(|quote| text.contains(quote))
+        || text.trim() != text;
+    if !needs_quoting {
+        return Ok(text.to_string());
+    }
+    let Some(quote) = options.quote else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("label {text:?} needs quoting, but quoting is disabled"),
+        ));
+    };
+    let escaped = text.replace(quote, &format!("{quote}{quote}"));
+    Ok(format!("{quote}{escaped}{quote}"))
+}
+
+// A field of a CSV record, with the 1-based position where it starts.
+struct CsvField {
+    text: String,
+    line: usize,
+    column: usize,
+}
+
+// Split CSV text into records, skipping empty lines.
+fn csv_records(text: &str, options: &CsvOptions) -> Result<Vec<Vec<CsvField>>, ParseError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut chars = text.chars().peekable();
+    let (mut line, mut column) = (1, 1);
+    while chars.peek().is_some() {
+        let (start_line, start_column) = (line, column);
+        let mut field = String::new();
+        let mut quoted = false;
+        if let Some(quote) = options.quote.filter(|&quote| chars.peek() == Some(&quote)) {
+            quoted = true;
+            chars.next();
+            column += 1;
+            loop {
+                match chars.next() {
+                    None => {
+                        return Err(ParseError::new(
+                            ParseErrorKind::Truncated,
+                            start_line,
+                            start_column,
+                        ));
+                    }
+                    Some(c) if c == quote => {
+                        column += 1;
+                        if chars.peek() == Some(&quote) {
+                            chars.next();
+                            column += 1;
+                            field.push(quote);
+                        } else {
+                            break;
+                        }
+                    }
+                    Some('\n') => {
+                        line += 1;
+                        column = 1;
+                        field.push('\n');
+                    }
+                    Some(c) => {
+                        column += 1;
+                        field.push(c);
+                    }
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c == options.delimiter || c == '\n' || c == '\r' {
+                    break;
+                }
+                field.push(c);
+                chars.next();
+                column += 1;
+            }
+        }
+        let empty_line = record.is_empty() && !quoted && field.is_empty();
+        record.push(CsvField {
+            text: field,
+            line: start_line,
+            column: start_column,
+        });
+
+        match chars.next() {
+            Some(c) if c == options.delimiter => {
+                column += 1;
+                // A trailing delimiter ends the input with an empty field.
+                if chars.peek().is_none() {
+                    record.push(CsvField {
+                        text: String::new(),
+                        line,
+                        column,
+                    });
+                }
+            }
+            None | Some('\n') | Some('\r') => {
+                if chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                let fields = std::mem::take(&mut record);
+                if !empty_line {
+                    records.push(fields);
+                }
+                line += 1;
+                column = 1;
+            }
+            Some(c) => {
+                return Err(ParseError::new(ParseErrorKind::BadCharacter, line, column)
+                    .with_token(c.to_string()));
+            }
+        }
+    }
+    if !record.is_empty() {
+        records.push(record);
+    }
+    Ok(records)
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_csv() {
+        let input = "\
+,needs water,\"lives, in water\",\"says \"\"hi\"\"\"\r
+frog,X,x,.\r
+\r
+dog,true,,FALSE\r
+";
+        let context = FormalContext::from_csv(input.as_bytes(), &CsvOptions::default());
+
+        assert_eq!(context.objects, ["frog", "dog"]);
+        assert_eq!(
+            context.attributes,
+            ["needs water", "lives, in water", "says \"hi\""]
+        );
+        assert_eq!(context.get_object_intent(0), &bitvec![1, 1, 0]);
+        assert_eq!(context.get_object_intent(1), &bitvec![1, 0, 0]);
+    }
+
+    #[test]
+    fn test_from_tsv_with_custom_markers() {
+        let options = CsvOptions {
+            quote: None,
+            true_markers: vec!["yes".into()],
+            false_markers: vec!["no".into()],
+            ..CsvOptions::tsv()
+        };
+        let input = "\ta \"b\"\tc\nx\tyes\tno\n";
+
+        let context = FormalContext::from_csv(input.as_bytes(), &options);
+
+        assert_eq!(context.attributes, ["a \"b\"", "c"]);
+        assert_eq!(context.get_object_intent(0), &bitvec![1, 0]);
+    }
+
+    #[test]
+    fn test_try_from_csv_errors() {
+        let options = CsvOptions::default();
+        let error =
+            |input: &str| FormalContext::try_from_csv(input.as_bytes(), &options).unwrap_err();
+
+        assert_eq!(error("").kind(), ParseErrorKind::BadHeader);
+
+        let err = error(",a,b\nx,1\n");
+        assert_eq!(err.kind(), ParseErrorKind::DimensionMismatch);
+        assert_eq!((err.line(), err.column()), (2, 3));
+
+        let err = error(",a,b\nx,1,maybe\n");
+        assert_eq!(err.kind(), ParseErrorKind::InvalidToken);
+        assert_eq!((err.line(), err.column()), (2, 5));
+        assert_eq!(err.token(), Some("maybe"));
+
+        let err = error(",a\nx,\"1\n");
+        assert_eq!(err.kind(), ParseErrorKind::Truncated);
+        assert_eq!((err.line(), err.column()), (2, 3));
+
+        let err = error(",\"a\"b\n");
+        assert_eq!(err.kind(), ParseErrorKind::BadCharacter);
+        assert_eq!((err.line(), err.column()), (1, 5));
+    }
+
+    #[test]
+    fn test_write_csv_round_trips() {
+        let context = FormalContext::new(
+            vec!["x, y".to_string(), "z".to_string()],
+            vec!["\"a\"".to_string(), "b\nc".to_string(), " d".to_string()],
+            vec![bitvec![1, 0, 1], bitvec![0, 1, 0]],
+        );
+        let options = CsvOptions::default();
+
+        let mut output = Vec::new();
+        context.write_csv(&mut output, &options).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output.clone()).unwrap(),
+            ",\"\"\"a\"\"\",\"b\nc\",\" d\"\n\"x, y\",1,0,1\nz,0,1,0\n"
+        );
+        assert_eq!(
+            FormalContext::from_csv(output.as_slice(), &options),
+            context
+        );
+    }
+
+    #[test]
+    fn test_write_csv_without_quoting() {
+        let context = FormalContext::new(vec!["a,b"], vec!["c"], vec![bitvec![1]]);
+        let options = CsvOptions {
+            quote: None,
+            ..CsvOptions::default()
+        };
+
+        let err = context.write_csv(Vec::new(), &options).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
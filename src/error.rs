@@ -0,0 +1,222 @@
+use std::fmt::Display;
+use std::io;
+
+use crate::Implication;
+
+/// The category of a [`ParseError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseErrorKind {
+    /// Reading from the underlying input failed.
+    Io,
+    /// The file header (e.g. the object and attribute counts of a `.cxt`
+    /// file) is missing or malformed.
+    BadHeader,
+    /// The input ended before all expected lines were read.
+    Truncated,
+    /// A relation row contains a character that is not a valid cell marker.
+    BadCharacter,
+    /// A relation row does not have the expected number of entries.
+    DimensionMismatch,
+    /// A token could not be parsed as a label (e.g. a non-numeric `.dat`
+    /// attribute).
+    InvalidToken,
+    /// A required element or XML attribute of a structured format (such as
+    /// ConExp `.cex`) is missing. The token names what is missing.
+    MissingElement,
+    /// Elements of a structured format are nested more deeply than the
+    /// parser allows. The token names the element that is too deep.
+    TooDeep,
+}
+
+/// An error produced while parsing a formal context.
+///
+/// Line and column numbers are 1-based. For errors that concern a whole line
+/// (such as a truncated file), the column is 1.
+#[derive(Debug)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    line: usize,
+    column: usize,
+    token: Option<String>,
+    source: Option<io::Error>,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind, line: usize, column: usize) -> Self {
+        Self {
+            kind,
+            line,
+            column,
+            token: None,
+            source: None,
+        }
+    }
+
+    pub(crate) fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub(crate) fn io(line: usize, err: io::Error) -> Self {
+        Self {
+            source: Some(err),
+            ..Self::new(ParseErrorKind::Io, line, 1)
+        }
+    }
+
+    /// Returns the category of this error.
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// Returns the 1-based line on which the error occurred.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the 1-based column at which the error occurred.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the offending token, if there is one.
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match self.kind {
+            ParseErrorKind::Io => write!(f, "read error")?,
+            ParseErrorKind::BadHeader => write!(f, "malformed header")?,
+            ParseErrorKind::Truncated => write!(f, "unexpected end of input")?,
+            ParseErrorKind::BadCharacter => write!(f, "invalid character")?,
+            ParseErrorKind::DimensionMismatch => {
+                write!(f, "row length doesn't match number of attributes")?
+            }
+            ParseErrorKind::InvalidToken => write!(f, "invalid token")?,
+            ParseErrorKind::MissingElement => write!(f, "missing element")?,
+            ParseErrorKind::TooDeep => write!(f, "elements nested too deeply")?,
+        }
+        if let Some(token) = &self.token {
+            write!(f, " {token:?}")?;
+        }
+        if let Some(source) = &self.source {
+            write!(f, ": {source}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|err| err as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// An error that ends an [attribute exploration](crate::FormalContext::explore).
+///
+/// Implications and counterexamples accepted before the error are kept in
+/// the explored context.
+#[derive(Debug)]
+pub enum ExplorationError<E> {
+    /// The expert failed to answer a question.
+    Expert(E),
+    /// The expert gave a counterexample to the contained question that does
+    /// not refute it: the object lacks a premise attribute or has every
+    /// conclusion attribute.
+    NotACounterexample(Implication),
+    /// The expert gave a counterexample that violates the contained,
+    /// previously accepted implication.
+    ViolatesAcceptedImplication(Implication),
+}
+
+impl<E: Display> Display for ExplorationError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExplorationError::Expert(err) => write!(f, "expert failed to answer: {err}"),
+            ExplorationError::NotACounterexample(_) => {
+                write!(f, "counterexample does not refute the implication")
+            }
+            ExplorationError::ViolatesAcceptedImplication(_) => {
+                write!(f, "counterexample violates an accepted implication")
+            }
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for ExplorationError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExplorationError::Expert(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// An error produced when combining formal contexts whose labels are not
+/// compatible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContextError {
+    /// The operation needs both contexts to have the same objects, in the
+    /// same order, but they differ.
+    ObjectMismatch,
+    /// The operation needs both contexts to have the same attributes, in the
+    /// same order, but they differ.
+    AttributeMismatch,
+    /// The object at the contained index of the second context also occurs
+    /// in the first, but the operation needs disjoint objects.
+    DuplicateObject(usize),
+    /// The attribute at the contained index of the second context also
+    /// occurs in the first, but the operation needs disjoint attributes.
+    DuplicateAttribute(usize),
+}
+
+impl Display for ContextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContextError::ObjectMismatch => write!(f, "contexts have different objects"),
+            ContextError::AttributeMismatch => write!(f, "contexts have different attributes"),
+            ContextError::DuplicateObject(i) => {
+                write!(
+                    f,
+                    "object {i} of the second context also occurs in the first"
+                )
+            }
+            ContextError::DuplicateAttribute(j) => {
+                write!(
+                    f,
+                    "attribute {j} of the second context also occurs in the first"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ContextError {}
+
+/// An error produced when a [`Scale`](crate::Scale) cannot place a value of
+/// a [`ManyValuedContext`](crate::ManyValuedContext).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScaleError {
+    /// The index of the object whose value could not be placed.
+    pub object: usize,
+    /// The index of the many-valued attribute whose scale rejected the value.
+    pub attribute: usize,
+}
+
+impl Display for ScaleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "value of attribute {} for object {} is not in its scale",
+            self.attribute, self.object
+        )
+    }
+}
+
+impl std::error::Error for ScaleError {}
@@ -0,0 +1,232 @@
+use crate::ExplorationError;
+use crate::FormalContext;
+use crate::Implication;
+use crate::ImplicationSet;
+use crate::bit_fiddling::*;
+
+use bitvec::prelude::*;
+use std::convert::Infallible;
+
+// Attribute exploration runs the canonical basis computation of
+// `canonical_basis.rs` against a context that is only partially known. For
+// every pseudo-intent candidate `P` (an attribute set closed under the
+// implications accepted so far, but not closed in the current context), the
+// expert is asked whether `P => P''` holds in the domain. A confirmed
+// implication joins the basis; a counterexample becomes a new object, which
+// shrinks `P''`, and the question is asked again until it is either accepted
+// or `P` is closed. Counterexamples must respect every accepted implication,
+// so the enumeration never has to backtrack.
+//
+// The context grows during the run, so closures are computed directly on
+// `BitVec`s rather than on the `u128` engines, and the accepted implications
+// are closed under with `ImplicationSet::close`.
+
+/// An answer to a question asked during attribute exploration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpertAnswer<A> {
+    /// The implication holds in the domain.
+    Accept,
+    /// The implication does not hold: the given object has every premise
+    /// attribute, but not every conclusion attribute. The bitset is the
+    /// object's intent.
+    Counterexample(A, BitVec),
+}
+
+/// An oracle that knows the domain described by a context and answers the
+/// questions of [`FormalContext::explore`].
+///
+/// Questions are implications over the attributes of the context being
+/// explored, with conclusions that only list attributes not already in the
+/// premise.
+pub trait Expert<A, B> {
+    /// The error returned when the expert cannot answer, e.g. because an
+    /// interactive user quit.
+    type Error;
+
+    /// Decides whether `question` holds in the domain, or gives an object
+    /// that refutes it.
+    ///
+    /// `context` is the current state of the explored context, including all
+    /// counterexamples given so far. A counterexample must also respect every
+    /// implication accepted earlier.
+    fn ask(
+        &mut self,
+        context: &FormalContext<A, B>,
+        question: &Implication,
+    ) -> Result<ExpertAnswer<A>, Self::Error>;
+}
+
+/// An [`Expert`] that answers from a known context, for testing and for
+/// replaying explorations offline.
+///
+/// Counterexamples are the objects of the hidden context, in order. Its
+/// attributes must correspond, by index, to those of the explored context.
+#[derive(Debug, Clone, Copy)]
+pub struct ContextExpert<'a, A, B> {
+    truth: &'a FormalContext<A, B>,
+}
+
+impl<'a, A, B> ContextExpert<'a, A, B> {
+    /// Creates an expert whose domain is the context `truth`.
+    pub fn new(truth: &'a FormalContext<A, B>) -> Self {
+        Self { truth }
+    }
+}
+
+impl<A: Clone, B> Expert<A, B> for ContextExpert<'_, A, B> {
+    type Error = Infallible;
+
+    fn ask(
+        &mut self,
+        context: &FormalContext<A, B>,
+        question: &Implication,
+    ) -> Result<ExpertAnswer<A>, Infallible> {
+        assert_eq!(context.attributes.len(), self.truth.attributes.len());
+        let counterexample = (0..self.truth.objects.len()).find(|&i| {
+            let intent = self.truth.get_object_intent(i);
+            is_subset(&question.premise, intent) && !is_subset(&question.conclusion, intent)
+        });
+        Ok(match counterexample {
+            Some(i) => ExpertAnswer::Counterexample(
+                self.truth.objects[i].clone(),
+                self.truth.get_object_intent(i).clone(),
+            ),
+            None => ExpertAnswer::Accept,
+        })
+    }
+}
+
+impl<A, B> FormalContext<A, B> {
+    /// Runs attribute exploration on this context, asking `expert` about
+    /// every implication that is valid in the context but not yet known to
+    /// hold in the domain.
+    ///
+    /// Counterexamples are appended to the objects of this context. When the
+    /// exploration finishes, every implication of the domain holds in the
+    /// context, and the accepted implications are returned: they are the
+    /// [canonical basis](FormalContext::canonical_basis) of the extended
+    /// context, in lectic order of their premises.
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first question the expert fails to answer, or the first
+    /// counterexample that does not refute its question or violates an
+    /// accepted implication; see [`ExplorationError`]. Counterexamples given
+    /// before the error stay in the context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a counterexample intent does not have one entry per
+    /// attribute.
+    pub fn explore<E: Expert<A, B>>(
+        &mut self,
+        expert: &mut E,
+    ) -> Result<Vec<Implication>, ExplorationError<E::Error>> {
+        let mut accepted = ImplicationSet::new(self.attributes.len());
+        let mut current = BitVec::repeat(false, self.attributes.len());
+        loop {
+            loop {
+                let closure = self.induce_r(&self.induce_l(&current));
+                if closure == current {
+                    break;
+                }
+                let question = Implication {
+                    conclusion: closure & !current.clone(),
+                    premise: current.clone(),
+                };
+                match expert
+                    .ask(self, &question)
+                    .map_err(ExplorationError::Expert)?
+                {
+                    ExpertAnswer::Accept => {
+                        accepted.push(question);
+                        break;
+                    }
+                    ExpertAnswer::Counterexample(object, intent) => {
+                        assert_eq!(intent.len(), self.attributes.len());
+                        if question.respected_by(&intent) {
+                            return Err(ExplorationError::NotACounterexample(question));
+                        }
+                        if let Some(violated) = accepted
+                            .implications()
+                            .iter()
+                            .find(|implication| !implication.respected_by(&intent))
+                        {
+                            return Err(ExplorationError::ViolatesAcceptedImplication(
+                                violated.clone(),
+                            ));
+                        }
+                        self.add_object(object, intent);
+                    }
+                }
+            }
+            match bitvec_next_closure(&current, |set| accepted.close(set)) {
+                Some(next) => current = next,
+                None => return Ok(accepted.implications().to_vec()),
+            }
+        }
+    }
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::lives_in_water;
+
+    #[test]
+    fn test_explore_from_empty_context() {
+        let truth = lives_in_water();
+        let mut context = FormalContext::zero_context(Vec::new(), truth.attributes.clone());
+
+        let basis = context.explore(&mut ContextExpert::new(&truth)).unwrap();
+
+        assert_eq!(basis, truth.canonical_basis());
+        assert_eq!(basis, context.canonical_basis());
+        assert!(context.objects.len() <= truth.objects.len());
+        assert!(context.validate());
+        // The explored context has the same intents as the hidden one.
+        assert_eq!(context.num_concepts(), truth.num_concepts());
+    }
+
+    #[test]
+    fn test_explore_complete_context_asks_no_counterexamples() {
+        let truth = lives_in_water();
+        let mut context = truth.clone();
+
+        let basis = context.explore(&mut ContextExpert::new(&truth)).unwrap();
+
+        assert_eq!(context, truth);
+        assert_eq!(basis, truth.canonical_basis());
+    }
+
+    // An expert that accepts nothing and answers with a fixed object.
+    struct Stubborn(BitVec);
+
+    impl Expert<&'static str, usize> for Stubborn {
+        type Error = Infallible;
+
+        fn ask(
+            &mut self,
+            _: &FormalContext<&'static str, usize>,
+            _: &Implication,
+        ) -> Result<ExpertAnswer<&'static str>, Infallible> {
+            Ok(ExpertAnswer::Counterexample("stub", self.0.clone()))
+        }
+    }
+
+    #[test]
+    fn test_explore_rejects_invalid_counterexamples() {
+        let mut context = FormalContext::zero_context(Vec::new(), vec![0, 1]);
+
+        // The first question is `[] => [0, 1]`; the full row does not refute it.
+        let result = context.explore(&mut Stubborn(bitvec![1, 1]));
+
+        assert!(matches!(
+            result,
+            Err(ExplorationError::NotACounterexample(question))
+                if question.premise == bitvec![0, 0]
+        ));
+        assert!(context.objects.is_empty());
+    }
+}
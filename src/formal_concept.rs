@@ -125,12 +125,43 @@ impl PartialOrd for RawFormalConcept {
     /// Concepts are ordered by subset containment of their extents.
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         if self.extent == other.extent {
-            Some(std::cmp::Ordering::Equal);
+            Some(std::cmp::Ordering::Equal)
         } else if is_subset(&self.extent, &other.extent) {
-            Some(std::cmp::Ordering::Less);
+            Some(std::cmp::Ordering::Less)
         } else if is_subset(&other.extent, &self.extent) {
-            Some(std::cmp::Ordering::Greater);
+            Some(std::cmp::Ordering::Greater)
+        } else {
+            None
         }
-        None
+    }
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    fn concept(extent: BitVec) -> RawFormalConcept {
+        RawFormalConcept {
+            extent,
+            intent: BitVec::new(),
+        }
+    }
+
+    #[test]
+    fn test_raw_concepts_ordered_by_extent_containment() {
+        let bottom = concept(bitvec![0, 0, 0]);
+        let left = concept(bitvec![1, 0, 0]);
+        let right = concept(bitvec![0, 1, 0]);
+        let top = concept(bitvec![1, 1, 0]);
+
+        assert_eq!(left.partial_cmp(&top), Some(Ordering::Less));
+        assert_eq!(bottom.partial_cmp(&left), Some(Ordering::Less));
+        assert_eq!(top.partial_cmp(&right), Some(Ordering::Greater));
+        assert_eq!(left.partial_cmp(&left.clone()), Some(Ordering::Equal));
+        assert_eq!(left.partial_cmp(&right), None);
+        assert_eq!(right.partial_cmp(&left), None);
+        assert!(left < top && top > right && left <= left.clone());
     }
 }
@@ -1,15 +1,16 @@
 use std::fmt::Display;
-use std::io::Read;
+use std::io::{self, Read, Write};
 
 use crate::FormalConcept;
 use crate::RawFormalConcept;
 use crate::bit_fiddling::*;
+use crate::{ParseError, ParseErrorKind};
 use bitvec::prelude::*;
 use std::sync::Arc;
 
 /// A binary relation between objects and attributes.
 ///
-/// One can query the relation by object and attribute indices with [`FormalContext::get_relation_idx`] or by labels with [`FormalContext::get_relation`]. The relation can be modified with [`FormalContext::modify_relation_idx`] or by labels with [`FormalContext::modify_relation`].
+/// One can query the relation by object and attribute indices with [`FormalContext::get_relation_idx`] or by labels with [`FormalContext::get_relation`]. The relation can be modified with [`FormalContext::modify_relation_idx`] or by labels with [`FormalContext::modify_relation`]. Objects and attributes are added with [`FormalContext::add_object`] and [`FormalContext::add_attribute`], removed with [`FormalContext::remove_objects`] and [`FormalContext::remove_attributes`], and selected with [`FormalContext::subcontext`].
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct FormalContext<A = String, B = String> {
     /// Object labels.
@@ -105,6 +106,93 @@ impl<A, B> FormalContext<A, B> {
                 .fold(BitVec::repeat(true, self.attributes.len()), |a, b| a & b),
         }
     }
+    /// Returns the dual context, in which objects and attributes swap roles.
+    ///
+    /// Both orientations of the relation are stored, so this only swaps them
+    /// and copies no bits. See [`FormalContext::dual`] for a non-consuming
+    /// version.
+    pub fn transpose(self) -> FormalContext<B, A> {
+        FormalContext {
+            objects: self.attributes,
+            attributes: self.objects,
+            relation: self.relation_transposed,
+            relation_transposed: self.relation,
+        }
+    }
+    /// Appends an object with the given intent.
+    ///
+    /// `intent` is a bitset over attribute indices; both the relation and its
+    /// transpose are extended.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `intent.len()` differs from the number of attributes.
+    pub fn add_object(&mut self, label: A, intent: BitVec) {
+        assert_eq!(intent.len(), self.attributes.len());
+        for (extent, bit) in self
+            .relation_transposed
+            .iter_mut()
+            .zip(intent.iter().by_vals())
+        {
+            extent.push(bit);
+        }
+        self.objects.push(label);
+        self.relation.push(intent);
+    }
+    /// Appends an attribute with the given extent.
+    ///
+    /// `extent` is a bitset over object indices; both the relation and its
+    /// transpose are extended.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `extent.len()` differs from the number of objects.
+    pub fn add_attribute(&mut self, label: B, extent: BitVec) {
+        assert_eq!(extent.len(), self.objects.len());
+        for (intent, bit) in self.relation.iter_mut().zip(extent.iter().by_vals()) {
+            intent.push(bit);
+        }
+        self.attributes.push(label);
+        self.relation_transposed.push(extent);
+    }
+    /// Removes the objects at the given indices.
+    ///
+    /// The remaining objects keep their order. Repeated indices are removed
+    /// once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is out of bounds.
+    pub fn remove_objects_idx(&mut self, indices: &[usize]) {
+        self.keep_objects(&retained(self.objects.len(), indices));
+    }
+    /// Removes the attributes at the given indices.
+    ///
+    /// The remaining attributes keep their order. Repeated indices are
+    /// removed once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is out of bounds.
+    pub fn remove_attributes_idx(&mut self, indices: &[usize]) {
+        self.keep_attributes(&retained(self.attributes.len(), indices));
+    }
+    // Keep only the objects whose bit in `keep` is set.
+    fn keep_objects(&mut self, keep: &BitVec) {
+        retain_by_mask(&mut self.objects, keep);
+        retain_by_mask(&mut self.relation, keep);
+        for extent in &mut self.relation_transposed {
+            *extent = select(extent, keep);
+        }
+    }
+    // Keep only the attributes whose bit in `keep` is set.
+    fn keep_attributes(&mut self, keep: &BitVec) {
+        retain_by_mask(&mut self.attributes, keep);
+        retain_by_mask(&mut self.relation_transposed, keep);
+        for intent in &mut self.relation {
+            *intent = select(intent, keep);
+        }
+    }
     /// Modifies the relation at the given object and attribute indices.
     ///
     /// Both the row-oriented relation and its transpose are updated.
@@ -167,28 +255,32 @@ impl<A, B> FormalContext<A, B> {
     /// A context is reduced when no row or column of the relation is the
     /// intersection of other rows or columns, respectively.
     pub fn is_reduced(&self) -> bool {
-        redundant_row(&self.relation).is_none()
-            && redundant_row(&self.relation_transposed).is_none()
+        irreducible_rows(&self.relation, self.attributes.len()).all()
+            && irreducible_rows(&self.relation_transposed, self.objects.len()).all()
     }
     /// Reduces this context in place.
     ///
     /// Redundant rows and columns are removed, so this can change `objects`,
-    /// `attributes`, and the relation matrix.
+    /// `attributes`, and the relation matrix. Of several identical rows or
+    /// columns, the last one is kept.
+    ///
+    /// See [`FormalContext::reduce_with_mapping`] to learn which rows and
+    /// columns were removed.
     pub fn reduce(&mut self) {
-        while let Some(i) = redundant_row(&self.relation) {
-            self.objects.remove(i);
-            self.relation.remove(i);
-            for c in &mut self.relation_transposed {
-                c.remove(i);
-            }
-        }
-        while let Some(i) = redundant_row(&self.relation_transposed) {
-            self.attributes.remove(i);
-            self.relation_transposed.remove(i);
-            for r in &mut self.relation {
-                r.remove(i);
-            }
-        }
+        self.reduce_tracked();
+    }
+    // Reduce in place, returning the original indices of the objects and
+    // attributes that remain. Objects are reduced first, then attributes of
+    // the remaining objects, and each is rebuilt once.
+    pub(crate) fn reduce_tracked(&mut self) -> (Vec<usize>, Vec<usize>) {
+        let objects = irreducible_rows(&self.relation, self.attributes.len());
+        self.keep_objects(&objects);
+        let attributes = irreducible_rows(&self.relation_transposed, self.objects.len());
+        self.keep_attributes(&attributes);
+        (
+            objects.iter_ones().collect(),
+            attributes.iter_ones().collect(),
+        )
     }
     /// Returns the fraction of relation entries that are true.
     ///
@@ -217,6 +309,37 @@ impl<A: Clone, B: Clone> FormalContext<A, B> {
         self.max_concept_raw()
             .to_formal_concept(std::sync::Arc::new(self.clone()))
     }
+    /// Returns the subcontext on the objects in `objects` and the attributes
+    /// in `attributes`, which are bitsets over object and attribute indices.
+    ///
+    /// Objects and attributes keep their order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of either mask differs from the number of objects
+    /// or attributes, respectively.
+    pub fn subcontext(&self, objects: &BitVec, attributes: &BitVec) -> Self {
+        assert_eq!(objects.len(), self.objects.len());
+        assert_eq!(attributes.len(), self.attributes.len());
+        Self {
+            objects: objects
+                .iter_ones()
+                .map(|i| self.objects[i].clone())
+                .collect(),
+            attributes: attributes
+                .iter_ones()
+                .map(|j| self.attributes[j].clone())
+                .collect(),
+            relation: objects
+                .iter_ones()
+                .map(|i| select(&self.relation[i], attributes))
+                .collect(),
+            relation_transposed: attributes
+                .iter_ones()
+                .map(|j| select(&self.relation_transposed[j], objects))
+                .collect(),
+        }
+    }
 }
 
 impl<A: Clone> FormalContext<A, A> {
@@ -283,6 +406,19 @@ impl<A: Eq, B: Eq> FormalContext<A, B> {
         }
         intent
     }
+    /// Builds an intent bitset from attribute labels, like
+    /// [`FormalContext::intent_from_attributes`], but returns `None` if a
+    /// label is not present in the context.
+    ///
+    /// <div class="warning">If there is more than one attribute with the same label, this will operate on the first match.</div>
+    pub fn try_intent_from_attributes(&self, attrs: impl IntoIterator<Item = B>) -> Option<BitVec> {
+        let mut intent = BitVec::repeat(false, self.attributes.len());
+        for attr in attrs {
+            let idx = self.attributes.iter().position(|a| *a == attr)?;
+            intent.set(idx, true);
+        }
+        Some(intent)
+    }
     /// Modifies the relation entry identified by object and attribute labels.
     ///
     /// <div class="warning">If there is more than one object or attribute with the same label, this will operate on the first match.</div>
@@ -303,9 +439,123 @@ impl<A: Eq, B: Eq> FormalContext<A, B> {
             .expect("Attribute not found in context");
         self.modify_relation_idx(obj_idx, attr_idx, value);
     }
+    /// Removes the objects with the given labels.
+    ///
+    /// <div class="warning">If there is more than one object with the same label, this will operate on the first match.</div>
+    ///
+    /// # Panics
+    ///
+    /// Panics if a label is not present in the context.
+    pub fn remove_objects(&mut self, objs: &[A]) {
+        let indices: Vec<usize> = objs
+            .iter()
+            .map(|obj| {
+                self.objects
+                    .iter()
+                    .position(|o| o == obj)
+                    .expect("Object not found in context")
+            })
+            .collect();
+        self.remove_objects_idx(&indices);
+    }
+    /// Removes the attributes with the given labels.
+    ///
+    /// <div class="warning">If there is more than one attribute with the same label, this will operate on the first match.</div>
+    ///
+    /// # Panics
+    ///
+    /// Panics if a label is not present in the context.
+    pub fn remove_attributes(&mut self, attrs: &[B]) {
+        let indices: Vec<usize> = attrs
+            .iter()
+            .map(|attr| {
+                self.attributes
+                    .iter()
+                    .position(|a| a == attr)
+                    .expect("Attribute not found in context")
+            })
+            .collect();
+        self.remove_attributes_idx(&indices);
+    }
+}
+
+impl<A: Display, B: Display> FormalContext<A, B> {
+    /// Writes this context in Burmeister `.cxt` format.
+    ///
+    /// Labels are written with their [`Display`] implementation, one per line.
+    /// The output can be read back with [`FormalContext::from_cxt`]; this
+    /// round-trips exactly as long as no label contains a line break or
+    /// leading/trailing whitespace, which the reader trims.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error produced by `output`.
+    pub fn write_cxt(&self, mut output: impl Write) -> io::Result<()> {
+        writeln!(output, "B")?;
+        writeln!(output)?;
+        writeln!(output, "{}", self.objects.len())?;
+        writeln!(output, "{}", self.attributes.len())?;
+        writeln!(output)?;
+        for obj in &self.objects {
+            writeln!(output, "{obj}")?;
+        }
+        for attr in &self.attributes {
+            writeln!(output, "{attr}")?;
+        }
+        for row in &self.relation {
+            let line: String = row
+                .iter()
+                .by_vals()
+                .map(|bit| if bit { 'X' } else { '.' })
+                .collect();
+            writeln!(output, "{line}")?;
+        }
+        output.flush()
+    }
+
+    /// Writes this context in simple `.dat` format.
+    ///
+    /// Each object becomes one line listing the labels of its attributes,
+    /// separated by spaces. Object labels are not written, and attributes that
+    /// no object has do not appear in the output. Consequently, reading the
+    /// output back with [`FormalContext::from_dat`] reproduces this context
+    /// only if it has the shape `from_dat` produces: objects labelled `obj0`,
+    /// `obj1`, ..., and every attribute an increasing integer held by at least
+    /// one object.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error produced by `output`.
+    pub fn write_dat(&self, mut output: impl Write) -> io::Result<()> {
+        for row in &self.relation {
+            let mut first = true;
+            for j in row.iter_ones() {
+                if !first {
+                    write!(output, " ")?;
+                }
+                write!(output, "{}", self.attributes[j])?;
+                first = false;
+            }
+            writeln!(output)?;
+        }
+        output.flush()
+    }
 }
 
 impl FormalContext {
+    /// Loads a formal context from Burmeister `.cxt` input.
+    ///
+    /// This is the panicking counterpart of [`FormalContext::try_from_cxt`];
+    /// see there for the expected format.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the input cannot be read as lines, is malformed, has invalid
+    /// dimensions, or contains relation rows with invalid characters or lengths.
+    pub fn from_cxt(input: impl Read) -> Self {
+        Self::try_from_cxt(input).unwrap_or_else(|err| panic!("{err}"))
+    }
+
     /// Loads a formal context from Burmeister `.cxt` input.
     ///
     /// The expected format is:
@@ -334,95 +584,93 @@ impl FormalContext {
     /// matrix row corresponds to one object and contains `.` for false and `X`
     /// for true.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the input cannot be read as lines, is malformed, has invalid
-    /// dimensions, or contains relation rows with invalid characters or lengths.
-    pub fn from_cxt(input: impl Read) -> Self {
-        use std::io::{BufRead, BufReader};
-        let reader = BufReader::new(input);
-        let mut lines = reader.lines();
+    /// Returns a [`ParseError`] if the input cannot be read as lines, is
+    /// truncated, has a malformed header, or contains relation rows with
+    /// invalid characters or lengths.
+    pub fn try_from_cxt(input: impl Read) -> Result<Self, ParseError> {
+        let mut lines = NumberedLines::new(input);
 
-        // Skip the first line (should be "B")
-        lines.next().expect("Missing first line").expect("IO error");
-
-        // Skip the blank line
-        lines.next().expect("Missing blank line").expect("IO error");
+        // The first line must be "B", followed by a blank line
+        lines.next_header_line("B")?;
+        lines.next_header_line("")?;
 
         // Read number of objects and attributes
-        let num_objects: usize = lines
-            .next()
-            .expect("Missing number of objects")
-            .expect("IO error")
-            .trim()
-            .parse()
-            .expect("Invalid number of objects");
-
-        let num_attributes: usize = lines
-            .next()
-            .expect("Missing number of attributes")
-            .expect("IO error")
-            .trim()
-            .parse()
-            .expect("Invalid number of attributes");
+        let num_objects = lines.next_count()?;
+        let num_attributes = lines.next_count()?;
 
         // Skip the blank line
-        lines.next().expect("Missing blank line").expect("IO error");
+        lines.next_header_line("")?;
+
+        // The counts are not trusted to size buffers up front: a bad header
+        // must fail on the missing lines, not on allocation.
 
         // Read object names
-        let mut objects = Vec::with_capacity(num_objects);
+        let mut objects = Vec::new();
         for _ in 0..num_objects {
-            let obj_name = lines
-                .next()
-                .expect("Missing object name")
-                .expect("IO error")
-                .trim()
-                .to_string();
-            objects.push(obj_name);
+            objects.push(lines.next_line()?.trim().to_string());
         }
 
         // Read attribute names
-        let mut attributes = Vec::with_capacity(num_attributes);
+        let mut attributes = Vec::new();
         for _ in 0..num_attributes {
-            let attr_name = lines
-                .next()
-                .expect("Missing attribute name")
-                .expect("IO error")
-                .trim()
-                .to_string();
-            attributes.push(attr_name);
+            attributes.push(lines.next_line()?.trim().to_string());
         }
 
         // Read relation matrix
-        let mut relation = Vec::with_capacity(num_objects);
+        let mut relation = Vec::new();
         for _ in 0..num_objects {
-            let row_str = lines
-                .next()
-                .expect("Missing relation row")
-                .expect("IO error")
-                .trim()
-                .to_string();
-
-            let mut row = BitVec::with_capacity(num_attributes);
-            for ch in row_str.chars() {
+            let line = lines.next_line()?;
+            let indent = line.len() - line.trim_start().len();
+            let row_str = line.trim();
+
+            let mut row = BitVec::new();
+            for (i, ch) in row_str.chars().enumerate() {
                 match ch {
                     'X' => row.push(true),
                     '.' => row.push(false),
-                    _ => panic!("Invalid character in matrix!"),
+                    _ => {
+                        return Err(ParseError::new(
+                            ParseErrorKind::BadCharacter,
+                            lines.line(),
+                            line[..indent].chars().count() + i + 1,
+                        )
+                        .with_token(ch));
+                    }
                 }
             }
 
             if row.len() != num_attributes {
-                panic!("Row length doesn't match number of attributes");
+                let column = line[..indent].chars().count() + row.len().min(num_attributes) + 1;
+                return Err(ParseError::new(
+                    ParseErrorKind::DimensionMismatch,
+                    lines.line(),
+                    column,
+                )
+                .with_token(row_str));
             }
 
             relation.push(row);
         }
-        Self::new(objects, attributes, relation)
+        Ok(Self::new(objects, attributes, relation))
     }
 }
 
 impl FormalContext<String, usize> {
+    /// Loads a formal context from simple `.dat` input.
+    ///
+    /// This is the panicking counterpart of [`FormalContext::try_from_dat`];
+    /// see there for the expected format.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the input cannot be read as lines or if any attribute token
+    /// cannot be parsed as `usize`.
+    pub fn from_dat(input: impl Read) -> Self {
+        Self::try_from_dat(input).unwrap_or_else(|err| panic!("{err}"))
+    }
+
     /// Loads a formal context from simple `.dat` input.
     ///
     /// Each line corresponds to one object and contains a space-separated list
@@ -432,31 +680,31 @@ impl FormalContext<String, usize> {
     ///
     /// Empty lines are meaningful: they create objects with no attributes.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the input cannot be read as lines or if any attribute token
-    /// cannot be parsed as `usize`.
-    pub fn from_dat(input: impl Read) -> Self {
+    /// Returns a [`ParseError`] if the input cannot be read as lines or if any
+    /// attribute token cannot be parsed as `usize`.
+    pub fn try_from_dat(input: impl Read) -> Result<Self, ParseError> {
         use std::collections::HashSet;
         use std::io::{BufRead, BufReader};
 
         let reader = BufReader::new(input);
-        let lines = reader.lines();
 
         // Collect all unique attributes from all lines
         let mut all_attributes = HashSet::new();
         let mut object_attributes: Vec<Vec<usize>> = Vec::new();
 
-        for line_result in lines {
-            let attrs: Vec<usize> = line_result
-                .expect("IO Error")
-                .split_whitespace()
-                .map(|s| s.parse().expect("Invalid attribute"))
-                .collect();
-            object_attributes.push(attrs.clone());
-            for attr in attrs {
+        for (i, line_result) in reader.lines().enumerate() {
+            let line = line_result.map_err(|err| ParseError::io(i + 1, err))?;
+            let mut attrs = Vec::new();
+            for (column, token) in tokens_with_columns(&line) {
+                let attr: usize = token.parse().map_err(|_| {
+                    ParseError::new(ParseErrorKind::InvalidToken, i + 1, column).with_token(token)
+                })?;
+                attrs.push(attr);
                 all_attributes.insert(attr);
             }
+            object_attributes.push(attrs);
         }
 
         let num_objects = object_attributes.len();
@@ -477,8 +725,108 @@ impl FormalContext<String, usize> {
             }
         }
 
-        Self::new(objects, attributes, relation)
+        Ok(Self::new(objects, attributes, relation))
+    }
+}
+
+// Line reader for the text formats. It remembers the 1-based number of the
+// most recently returned line so parse errors can point back into the input.
+struct NumberedLines<R> {
+    lines: std::io::Lines<std::io::BufReader<R>>,
+    line: usize,
+}
+
+impl<R: Read> NumberedLines<R> {
+    fn new(input: R) -> Self {
+        use std::io::BufRead;
+        Self {
+            lines: std::io::BufReader::new(input).lines(),
+            line: 0,
+        }
+    }
+
+    fn line(&self) -> usize {
+        self.line
+    }
+
+    fn next_line(&mut self) -> Result<String, ParseError> {
+        self.line += 1;
+        match self.lines.next() {
+            Some(Ok(line)) => Ok(line),
+            Some(Err(err)) => Err(ParseError::io(self.line, err)),
+            None => Err(ParseError::new(ParseErrorKind::Truncated, self.line, 1)),
+        }
+    }
+
+    // Read a header line that must be `expected`, up to surrounding
+    // whitespace.
+    fn next_header_line(&mut self, expected: &str) -> Result<(), ParseError> {
+        let line = self.next_line()?;
+        let token = line.trim();
+        if token == expected {
+            return Ok(());
+        }
+        let column = line[..line.len() - line.trim_start().len()].chars().count() + 1;
+        Err(ParseError::new(ParseErrorKind::BadHeader, self.line, column).with_token(token))
+    }
+
+    // Read a header line holding a single non-negative integer.
+    fn next_count(&mut self) -> Result<usize, ParseError> {
+        let line = self.next_line()?;
+        let token = line.trim();
+        token.parse().map_err(|_| {
+            let column = line[..line.len() - line.trim_start().len()].chars().count() + 1;
+            ParseError::new(ParseErrorKind::BadHeader, self.line, column).with_token(token)
+        })
+    }
+}
+
+// Split a line on whitespace, pairing each token with its 1-based character
+// column.
+fn tokens_with_columns(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = None;
+    let mut column = 0;
+    let mut start_column = 0;
+    let mut tokens = Vec::new();
+    for (byte, ch) in line.char_indices() {
+        column += 1;
+        match (ch.is_whitespace(), start) {
+            (false, None) => {
+                start = Some(byte);
+                start_column = column;
+            }
+            (true, Some(s)) => {
+                tokens.push((start_column, &line[s..byte]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        tokens.push((start_column, &line[s..]));
+    }
+    tokens.into_iter()
+}
+
+// The mask of `len` entries with `indices` unset.
+fn retained(len: usize, indices: &[usize]) -> BitVec {
+    let mut keep = BitVec::repeat(true, len);
+    for &i in indices {
+        assert!(i < len, "Index {i} out of bounds");
+        keep.set(i, false);
     }
+    keep
+}
+
+// Keep the items of `items` whose bit in `keep` is set.
+fn retain_by_mask<T>(items: &mut Vec<T>, keep: &BitVec) {
+    let mut bits = keep.iter().by_vals();
+    items.retain(|_| bits.next().unwrap_or(false));
+}
+
+// The bits of `bits` at the positions set in `mask`, in order.
+fn select(bits: &BitVec, mask: &BitVec) -> BitVec {
+    mask.iter_ones().map(|i| bits[i]).collect()
 }
 
 // Tests
@@ -514,6 +862,111 @@ mod tests {
         assert!(context.is_reduced());
     }
 
+    // The original reduction: repeatedly remove the first row that is the
+    // intersection of other rows, then do the same for columns.
+    fn reduce_naive<A, B>(context: &mut FormalContext<A, B>) {
+        fn redundant_row(x: &[BitVec], width: usize) -> Option<usize> {
+            (0..x.len()).find(|&i| {
+                let mut meet = BitVec::repeat(true, width);
+                for j in 0..x.len() {
+                    if i != j && is_subset(&x[i], &x[j]) {
+                        meet &= &x[j];
+                    }
+                }
+                meet == x[i]
+            })
+        }
+        while let Some(i) = redundant_row(&context.relation, context.attributes.len()) {
+            context.remove_objects_idx(&[i]);
+        }
+        while let Some(j) = redundant_row(&context.relation_transposed, context.objects.len()) {
+            context.remove_attributes_idx(&[j]);
+        }
+    }
+
+    #[test]
+    fn test_reduction_matches_naive_reduction() {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        for (objects, attributes, density) in [(12, 6, 2), (30, 10, 3), (40, 8, 5), (25, 0, 2)] {
+            let relation = (0..objects)
+                .map(|_| {
+                    (0..attributes)
+                        .map(|_| {
+                            state = state
+                                .wrapping_mul(6364136223846793005)
+                                .wrapping_add(1442695040888963407);
+                            !(state >> 33).is_multiple_of(density)
+                        })
+                        .collect()
+                })
+                .collect();
+            let context =
+                FormalContext::new((0..objects).collect(), (0..attributes).collect(), relation);
+
+            let mut fast = context.clone();
+            fast.reduce();
+            let mut naive = context.clone();
+            reduce_naive(&mut naive);
+
+            assert_eq!(fast, naive);
+            assert!(fast.validate());
+            assert!(fast.is_reduced());
+            assert_eq!(fast.num_concepts(), context.num_concepts());
+        }
+    }
+
+    #[test]
+    fn test_add_and_remove() {
+        let mut context = FormalContext::new(
+            vec!["a", "b"],
+            vec!["1", "2"],
+            vec![bitvec![1, 0], bitvec![0, 1]],
+        );
+        context.add_object("c", bitvec![1, 1]);
+        context.add_attribute("3", bitvec![0, 1, 1]);
+        assert_eq!(context.objects, ["a", "b", "c"]);
+        assert_eq!(context.get_object_intent(1), &bitvec![0, 1, 1]);
+        assert_eq!(context.get_attribute_extent(2), &bitvec![0, 1, 1]);
+        assert!(context.validate());
+
+        context.remove_objects(&["a"]);
+        context.remove_attributes_idx(&[1, 1]);
+        assert_eq!(context.objects, ["b", "c"]);
+        assert_eq!(context.attributes, ["1", "3"]);
+        assert_eq!(context.get_object_intent(0), &bitvec![0, 1]);
+        assert_eq!(context.get_object_intent(1), &bitvec![1, 1]);
+        assert!(context.validate());
+
+        context.remove_objects_idx(&[0, 1]);
+        assert!(context.objects.is_empty());
+        assert_eq!(context.get_attribute_extent(1), &bitvec![]);
+        assert!(context.validate());
+    }
+
+    #[test]
+    fn test_subcontext() {
+        let context = FormalContext::new(
+            vec!["a", "b", "c"],
+            vec!["1", "2", "3"],
+            vec![bitvec![1, 0, 1], bitvec![1, 1, 0], bitvec![0, 1, 1]],
+        );
+
+        let subcontext = context.subcontext(&bitvec![1, 0, 1], &bitvec![0, 1, 1]);
+
+        assert_eq!(subcontext.objects, ["a", "c"]);
+        assert_eq!(subcontext.attributes, ["2", "3"]);
+        assert_eq!(subcontext.get_object_intent(0), &bitvec![0, 1]);
+        assert_eq!(subcontext.get_object_intent(1), &bitvec![1, 1]);
+        assert!(subcontext.validate());
+    }
+
+    #[test]
+    #[should_panic(expected = "Object not found")]
+    fn test_remove_objects_rejects_unknown_labels() {
+        let mut context = FormalContext::zero_context(vec!["a"], vec!["1"]);
+        context.remove_objects(&["b"]);
+    }
+
     #[test]
     fn test_from_dat_uses_usize_attributes() {
         let context = FormalContext::from_dat("2 10\n1 2\n10\n".as_bytes());
@@ -549,4 +1002,109 @@ X.
         assert!(context.get_relation(&"obj1".to_string(), &"attr1".to_string()));
         assert!(!context.get_relation(&"obj0".to_string(), &"attr1".to_string()));
     }
+
+    #[test]
+    fn test_try_from_cxt_reports_bad_character() {
+        let input = b"B\n\n2\n2\n\nobj0\nobj1\nattr0\nattr1\nX.\n.Y\n";
+
+        let err = FormalContext::try_from_cxt(&input[..]).unwrap_err();
+
+        assert_eq!(err.kind(), ParseErrorKind::BadCharacter);
+        assert_eq!(err.line(), 11);
+        assert_eq!(err.column(), 2);
+        assert_eq!(err.token(), Some("Y"));
+    }
+
+    #[test]
+    fn test_try_from_cxt_reports_header_and_truncation() {
+        let err = FormalContext::try_from_cxt(&b"B\n\ntwo\n2\n"[..]).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::BadHeader);
+        assert_eq!((err.line(), err.column()), (3, 1));
+        assert_eq!(err.token(), Some("two"));
+
+        let err = FormalContext::try_from_cxt(&b"B\n\n1\n2\n\nobj0\nattr0\n"[..]).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::Truncated);
+        assert_eq!(err.line(), 8);
+
+        let err =
+            FormalContext::try_from_cxt(&b"B\n\n1\n2\n\nobj0\nattr0\nattr1\nX\n"[..]).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::DimensionMismatch);
+        assert_eq!((err.line(), err.column()), (9, 2));
+    }
+
+    #[test]
+    fn test_intent_from_attributes_strict_and_lenient() {
+        let context = FormalContext::new(vec!["g"], vec!["a", "b", "c"], vec![bitvec![1, 0, 1]]);
+
+        assert_eq!(context.intent_from_attributes(["c", "x"]), bitvec![0, 0, 1]);
+        assert_eq!(
+            context.try_intent_from_attributes(["c", "a"]),
+            Some(bitvec![1, 0, 1])
+        );
+        assert_eq!(context.try_intent_from_attributes(["c", "x"]), None);
+    }
+
+    #[test]
+    fn test_try_from_cxt_rejects_missing_magic_line() {
+        let err = FormalContext::try_from_cxt(&b"1 2\n3 4\n"[..]).unwrap_err();
+
+        assert_eq!(err.kind(), ParseErrorKind::BadHeader);
+        assert_eq!((err.line(), err.column()), (1, 1));
+        assert_eq!(err.token(), Some("1 2"));
+    }
+
+    #[test]
+    fn test_try_from_cxt_rejects_non_blank_separators() {
+        let err = FormalContext::try_from_cxt(&b"B\n1\n1\n\nobj0\nattr0\nX\n"[..]).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::BadHeader);
+        assert_eq!((err.line(), err.column()), (2, 1));
+
+        let err = FormalContext::try_from_cxt(&b"B\n\n1\n1\n  obj0\nattr0\nX\n"[..]).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::BadHeader);
+        assert_eq!((err.line(), err.column()), (5, 3));
+        assert_eq!(err.token(), Some("obj0"));
+    }
+
+    #[test]
+    fn test_try_from_cxt_rejects_huge_counts() {
+        let input = format!("B\n\n{}\n{}\n\nobj0\n", usize::MAX, usize::MAX);
+
+        let err = FormalContext::try_from_cxt(input.as_bytes()).unwrap_err();
+
+        assert_eq!(err.kind(), ParseErrorKind::Truncated);
+        assert_eq!(err.line(), 7);
+    }
+
+    #[test]
+    fn test_try_from_dat_reports_invalid_token() {
+        let err = FormalContext::try_from_dat("1 2\n3  x4 5\n".as_bytes()).unwrap_err();
+
+        assert_eq!(err.kind(), ParseErrorKind::InvalidToken);
+        assert_eq!((err.line(), err.column()), (2, 4));
+        assert_eq!(err.token(), Some("x4"));
+        assert_eq!(err.to_string(), "2:4: invalid token \"x4\"");
+    }
+
+    #[test]
+    fn test_write_cxt_round_trips() {
+        let input = b"B\n\n3\n2\n\nobj 0\nobj1\nobj2\nattr0\nattr 1\nX.\n.X\nXX\n";
+        let context = FormalContext::from_cxt(&input[..]);
+
+        let mut buffer = Vec::new();
+        context.write_cxt(&mut buffer).unwrap();
+
+        assert_eq!(buffer, input);
+        assert_eq!(FormalContext::from_cxt(&buffer[..]), context);
+    }
+
+    #[test]
+    fn test_write_dat_round_trips() {
+        let context = FormalContext::from_dat("2 10\n\n1 2\n10\n".as_bytes());
+
+        let mut buffer = Vec::new();
+        context.write_dat(&mut buffer).unwrap();
+
+        assert_eq!(buffer, b"2 10\n\n1 2\n10\n");
+        assert_eq!(FormalContext::from_dat(&buffer[..]), context);
+    }
 }
@@ -0,0 +1,93 @@
+use crate::FormalContext;
+
+use bitvec::prelude::*;
+use std::fmt::Debug;
+
+/// An attribute implication `premise => conclusion`.
+///
+/// Both sides are bitsets over the attribute indices of some
+/// [`FormalContext`]. Like [`RawFormalConcept`](crate::RawFormalConcept), an
+/// implication does not store labels; use [`Implication::display`] to print
+/// it with the attribute labels of a context.
+///
+/// `premise` and `conclusion` are exported for ease of use.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Implication {
+    /// `premise[j]` is `true` if and only if the j-th attribute is in the premise.
+    pub premise: BitVec,
+    /// `conclusion[j]` is `true` if and only if the j-th attribute is in the conclusion.
+    pub conclusion: BitVec,
+}
+
+/// Helper for printing an [`Implication`] with labels; see
+/// [`Implication::display`].
+pub struct ImplicationDisplay<'a, A, B> {
+    implication: &'a Implication,
+    context: &'a FormalContext<A, B>,
+}
+
+impl Implication {
+    /// Returns whether every object of `context` that has all premise
+    /// attributes also has all conclusion attributes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bitset lengths do not match the number of attributes of
+    /// `context`.
+    pub fn holds_in<A, B>(&self, context: &FormalContext<A, B>) -> bool {
+        assert_eq!(self.premise.len(), context.attributes.len());
+        assert_eq!(self.conclusion.len(), context.attributes.len());
+        let extent = context.induce_l(&self.premise);
+        let closure = context.induce_r(&extent);
+        crate::bit_fiddling::is_subset(&self.conclusion, &closure)
+    }
+
+    /// Returns whether the attribute set `set` respects this implication:
+    /// it lacks some premise attribute or has every conclusion attribute.
+    ///
+    /// An implication holds in a context exactly when every object intent
+    /// respects it.
+    pub fn respected_by(&self, set: &BitVec) -> bool {
+        !crate::bit_fiddling::is_subset(&self.premise, set)
+            || crate::bit_fiddling::is_subset(&self.conclusion, set)
+    }
+
+    /// Iterates over the labels of the premise attributes in `context`.
+    pub fn premise_names_iter<'a, A, B>(
+        &'a self,
+        context: &'a FormalContext<A, B>,
+    ) -> impl Iterator<Item = &'a B> {
+        self.premise.iter_ones().map(|j| &context.attributes[j])
+    }
+
+    /// Iterates over the labels of the conclusion attributes in `context`.
+    pub fn conclusion_names_iter<'a, A, B>(
+        &'a self,
+        context: &'a FormalContext<A, B>,
+    ) -> impl Iterator<Item = &'a B> {
+        self.conclusion.iter_ones().map(|j| &context.attributes[j])
+    }
+
+    /// Returns a value that displays this implication with the attribute
+    /// labels of `context`, e.g. `["a", "b"] => ["c"]`.
+    pub fn display<'a, A, B>(
+        &'a self,
+        context: &'a FormalContext<A, B>,
+    ) -> ImplicationDisplay<'a, A, B> {
+        ImplicationDisplay {
+            implication: self,
+            context,
+        }
+    }
+}
+
+impl<A, B: Debug> std::fmt::Display for ImplicationDisplay<'_, A, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let premise: Vec<_> = self.implication.premise_names_iter(self.context).collect();
+        let conclusion: Vec<_> = self
+            .implication
+            .conclusion_names_iter(self.context)
+            .collect();
+        write!(f, "{:?} => {:?}", premise, conclusion)
+    }
+}
@@ -0,0 +1,551 @@
+use crate::ClosureOperator;
+use crate::FormalContext;
+use crate::Implication;
+use crate::bit_fiddling::{is_subset, lectic_cmp};
+
+use bitvec::prelude::*;
+use std::fmt::{self, Debug, Display};
+
+/// A set of [`Implication`]s over a fixed number of attributes.
+///
+/// Unlike a plain `Vec<Implication>`, an implication set knows the number of
+/// attributes its implications range over, and checks that every implication
+/// matches it. Implication sets can be reasoned about without a context:
+/// [`ImplicationSet::close`] computes the closure of an attribute set,
+/// [`ImplicationSet::implies`] decides entailment, and
+/// [`ImplicationSet::minimal_cover`] finds the smallest equivalent set. They
+/// can also be checked against a context with [`ImplicationSet::holds_in`] and
+/// [`ImplicationSet::violations`].
+///
+/// An implication set is a [`ClosureOperator`], so the attribute sets closed
+/// under its implications can be enumerated with
+/// [`ClosureOperator::closed_sets`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImplicationSet {
+    attributes_len: usize,
+    implications: Vec<Implication>,
+}
+
+/// An implication of an [`ImplicationSet`] that does not hold in a context,
+/// together with the objects violating it; see
+/// [`ImplicationSet::violations`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Violation {
+    /// The index of the implication in the [`ImplicationSet`].
+    pub implication: usize,
+    /// `objects[i]` is `true` if and only if the i-th object has every
+    /// premise attribute but lacks some conclusion attribute.
+    pub objects: BitVec,
+}
+
+/// Helper for printing an [`ImplicationSet`] with labels; see
+/// [`ImplicationSet::display`].
+pub struct ImplicationSetDisplay<'a, A, B> {
+    implications: &'a ImplicationSet,
+    context: &'a FormalContext<A, B>,
+}
+
+impl ImplicationSet {
+    /// Creates an empty implication set over `attributes_len` attributes.
+    pub fn new(attributes_len: usize) -> Self {
+        Self {
+            attributes_len,
+            implications: Vec::new(),
+        }
+    }
+
+    /// Creates an implication set over `attributes_len` attributes from the
+    /// given implications, keeping their order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the premise or conclusion of an implication is not a bitset
+    /// of length `attributes_len`.
+    pub fn from_implications(attributes_len: usize, implications: Vec<Implication>) -> Self {
+        let mut set = Self::new(attributes_len);
+        for implication in implications {
+            set.push(implication);
+        }
+        set
+    }
+
+    /// Appends an implication.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the premise or conclusion is not a bitset over the attributes
+    /// of this set.
+    pub fn push(&mut self, implication: Implication) {
+        assert_eq!(implication.premise.len(), self.attributes_len);
+        assert_eq!(implication.conclusion.len(), self.attributes_len);
+        self.implications.push(implication);
+    }
+
+    /// Returns the number of attributes the implications range over.
+    pub fn attributes_len(&self) -> usize {
+        self.attributes_len
+    }
+
+    /// Returns the implications, in order.
+    pub fn implications(&self) -> &[Implication] {
+        &self.implications
+    }
+
+    /// Returns the number of implications.
+    pub fn len(&self) -> usize {
+        self.implications.len()
+    }
+
+    /// Returns whether there are no implications.
+    pub fn is_empty(&self) -> bool {
+        self.implications.is_empty()
+    }
+
+    /// Returns the closure of `set` under the implications: the smallest
+    /// superset of `set` that contains the conclusion of every implication
+    /// whose premise it contains.
+    ///
+    /// The closure is computed with LinClosure, in time linear in the total
+    /// size of the implications.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `set` is not a bitset over the attributes of this set.
+    pub fn close(&self, set: &BitVec) -> BitVec {
+        assert_eq!(set.len(), self.attributes_len);
+        lin_closure(&self.implications.iter().collect::<Vec<_>>(), set)
+    }
+
+    /// Returns whether the implication `premise => conclusion` follows from
+    /// this set, i.e. holds in every context in which all implications of this
+    /// set hold.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `premise` or `conclusion` is not a bitset over the attributes
+    /// of this set.
+    pub fn implies(&self, premise: &BitVec, conclusion: &BitVec) -> bool {
+        assert_eq!(conclusion.len(), self.attributes_len);
+        is_subset(conclusion, &self.close(premise))
+    }
+
+    /// Returns whether `implication` follows from this set; see
+    /// [`ImplicationSet::implies`].
+    pub fn entails(&self, implication: &Implication) -> bool {
+        self.implies(&implication.premise, &implication.conclusion)
+    }
+
+    /// Returns whether this set and `other` are equivalent: each implies every
+    /// implication of the other, so they have the same closed sets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two sets range over different numbers of attributes.
+    pub fn is_equivalent(&self, other: &Self) -> bool {
+        assert_eq!(self.attributes_len, other.attributes_len);
+        other.implications.iter().all(|i| self.entails(i))
+            && self.implications.iter().all(|i| other.entails(i))
+    }
+
+    /// Removes every implication that follows from the remaining ones.
+    ///
+    /// Implications are considered in order, so of two equivalent
+    /// implications the later one is kept. The result is equivalent to the
+    /// original set and contains no redundant implication, but unlike
+    /// [`ImplicationSet::minimal_cover`] it keeps the premises and conclusions
+    /// of the implications as they are.
+    pub fn remove_redundant(&mut self) {
+        let mut kept = bitvec![1; self.implications.len()];
+        for i in 0..self.implications.len() {
+            let implication = &self.implications[i];
+            let others = others(&self.implications, i, &kept);
+            if is_subset(
+                &implication.conclusion,
+                &lin_closure(&others, &implication.premise),
+            ) {
+                kept.set(i, false);
+            }
+        }
+        let mut kept = kept.into_iter();
+        self.implications.retain(|_| kept.next().unwrap());
+    }
+
+    /// Returns the minimal cover of this set: the equivalent implication set
+    /// with the fewest implications.
+    ///
+    /// The result is the canonical (Duquenne–Guigues) basis of the closure
+    /// operator defined by this set. Its premises are the pseudo-closed sets,
+    /// in lectic order, and each conclusion contains only the attributes
+    /// implied by the premise that are not already in it. For the implications
+    /// valid in a context, this is exactly
+    /// [`FormalContext::canonical_basis`].
+    pub fn minimal_cover(&self) -> Self {
+        // The algorithm of Bazhanov & Obiedkov, "Optimizations in computing
+        // the Duquenne–Guigues basis of implications" (2014): first replace
+        // every conclusion by the closure of the premise under all
+        // implications, then every premise by its closure under the others,
+        // dropping implications whose premise becomes closed. Conclusions
+        // hold the whole closure until the end.
+        let mut implications = self.implications.clone();
+        let mut kept = bitvec![1; implications.len()];
+        for i in 0..implications.len() {
+            let implication = &implications[i];
+            let closure = lin_closure(
+                &others(&implications, i, &kept),
+                &(implication.premise.clone() | &implication.conclusion),
+            );
+            implications[i].conclusion = closure;
+        }
+
+        for i in 0..implications.len() {
+            let premise = lin_closure(&others(&implications, i, &kept), &implications[i].premise);
+            if premise == implications[i].conclusion {
+                kept.set(i, false);
+            } else {
+                implications[i].premise = premise;
+            }
+        }
+
+        let mut kept = kept.into_iter();
+        implications.retain(|_| kept.next().unwrap());
+        for implication in &mut implications {
+            implication.conclusion &= !implication.premise.clone();
+        }
+        implications.sort_by(|a, b| lectic_cmp(&a.premise, &b.premise));
+        Self {
+            attributes_len: self.attributes_len,
+            implications,
+        }
+    }
+
+    /// Returns whether every implication of this set holds in `context`; see
+    /// [`Implication::holds_in`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `context` does not have exactly as many attributes as this
+    /// set.
+    pub fn holds_in<A, B>(&self, context: &FormalContext<A, B>) -> bool {
+        assert_eq!(context.attributes.len(), self.attributes_len);
+        self.implications.iter().all(|i| i.holds_in(context))
+    }
+
+    /// Returns the implications of this set that do not hold in `context`,
+    /// each with the objects that violate it, in the order of the
+    /// implications.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `context` does not have exactly as many attributes as this
+    /// set.
+    pub fn violations<A, B>(&self, context: &FormalContext<A, B>) -> Vec<Violation> {
+        assert_eq!(context.attributes.len(), self.attributes_len);
+        self.implications
+            .iter()
+            .enumerate()
+            .filter_map(|(i, implication)| {
+                let both = implication.premise.clone() | &implication.conclusion;
+                let objects = context.induce_l(&implication.premise) & !context.induce_l(&both);
+                objects.any().then_some(Violation {
+                    implication: i,
+                    objects,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns a value that displays this set with the attribute labels of
+    /// `context`, one implication per line.
+    pub fn display<'a, A, B>(
+        &'a self,
+        context: &'a FormalContext<A, B>,
+    ) -> ImplicationSetDisplay<'a, A, B> {
+        ImplicationSetDisplay {
+            implications: self,
+            context,
+        }
+    }
+}
+
+impl ClosureOperator for ImplicationSet {
+    fn elements_len(&self) -> usize {
+        self.attributes_len
+    }
+
+    fn close(&self, set: &BitVec) -> BitVec {
+        ImplicationSet::close(self, set)
+    }
+}
+
+impl Violation {
+    /// Iterates over the labels of the violating objects in `context`.
+    pub fn object_names_iter<'a, A, B>(
+        &'a self,
+        context: &'a FormalContext<A, B>,
+    ) -> impl Iterator<Item = &'a A> {
+        self.objects.iter_ones().map(|i| &context.objects[i])
+    }
+}
+
+impl<A: Eq, B: Eq> FormalContext<A, B> {
+    /// Returns the implication `premise => conclusion` between the attributes
+    /// with the given labels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a label is not an attribute of this context.
+    pub fn implication(
+        &self,
+        premise: impl IntoIterator<Item = B>,
+        conclusion: impl IntoIterator<Item = B>,
+    ) -> Implication {
+        Implication {
+            premise: self
+                .try_intent_from_attributes(premise)
+                .expect("Attribute not found in context"),
+            conclusion: self
+                .try_intent_from_attributes(conclusion)
+                .expect("Attribute not found in context"),
+        }
+    }
+}
+
+impl<A, B: Debug> Display for ImplicationSetDisplay<'_, A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for implication in &self.implications.implications {
+            writeln!(f, "{}", implication.display(self.context))?;
+        }
+        Ok(())
+    }
+}
+
+// The implications other than the i-th whose bit in `kept` is set.
+fn others<'a>(implications: &'a [Implication], i: usize, kept: &BitVec) -> Vec<&'a Implication> {
+    implications
+        .iter()
+        .enumerate()
+        .filter(|&(j, _)| j != i && kept[j])
+        .map(|(_, implication)| implication)
+        .collect()
+}
+
+// LinClosure (Beeri & Bernstein, 1979). Every implication counts the premise
+// attributes still missing from the closure, and each attribute lists the
+// implications whose premise contains it. Attributes are processed once as
+// they enter the closure, and an implication fires when its count drops to
+// zero, so every implication is touched once per premise attribute.
+fn lin_closure(implications: &[&Implication], set: &BitVec) -> BitVec {
+    let mut closure = set.clone();
+    let mut missing = Vec::with_capacity(implications.len());
+    let mut watchers = vec![Vec::new(); set.len()];
+    let mut ready = Vec::new();
+    for (i, implication) in implications.iter().enumerate() {
+        missing.push(implication.premise.count_ones());
+        for j in implication.premise.iter_ones() {
+            watchers[j].push(i);
+        }
+        if missing[i] == 0 {
+            ready.push(i);
+        }
+    }
+
+    let mut pending: Vec<usize> = set.iter_ones().collect();
+    loop {
+        while let Some(i) = ready.pop() {
+            for j in implications[i].conclusion.iter_ones() {
+                if !closure[j] {
+                    closure.set(j, true);
+                    pending.push(j);
+                }
+            }
+        }
+        let Some(j) = pending.pop() else {
+            return closure;
+        };
+        for &i in &watchers[j] {
+            missing[i] -= 1;
+            if missing[i] == 0 {
+                ready.push(i);
+            }
+        }
+    }
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lcg(seed: u64) -> impl FnMut() -> u64 {
+        let mut state = seed;
+        move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            state >> 33
+        }
+    }
+
+    fn random_set(next: &mut impl FnMut() -> u64, len: usize, density: u64) -> BitVec {
+        (0..len).map(|_| next() % 100 < density).collect()
+    }
+
+    fn random_context(seed: u64, objects: usize, attributes: usize) -> FormalContext<usize, usize> {
+        let mut next = lcg(seed);
+        let relation = (0..objects)
+            .map(|_| random_set(&mut next, attributes, 50))
+            .collect();
+        FormalContext::new((0..objects).collect(), (0..attributes).collect(), relation)
+    }
+
+    // Close `set` by applying implications until nothing changes.
+    fn naive_closure(implications: &ImplicationSet, set: &BitVec) -> BitVec {
+        let mut closure = set.clone();
+        loop {
+            let before = closure.clone();
+            for implication in implications.implications() {
+                if is_subset(&implication.premise, &closure) {
+                    closure |= &implication.conclusion;
+                }
+            }
+            if closure == before {
+                return closure;
+            }
+        }
+    }
+
+    #[test]
+    fn test_lin_closure_matches_naive_closure() {
+        let mut next = lcg(1);
+        for _ in 0..20 {
+            let mut implications = ImplicationSet::new(12);
+            for _ in 0..next() % 15 {
+                implications.push(Implication {
+                    premise: random_set(&mut next, 12, 20),
+                    conclusion: random_set(&mut next, 12, 15),
+                });
+            }
+            for _ in 0..20 {
+                let set = random_set(&mut next, 12, 25);
+                assert_eq!(implications.close(&set), naive_closure(&implications, &set));
+            }
+        }
+    }
+
+    #[test]
+    fn test_minimal_cover_is_canonical_basis() {
+        for seed in 0..10 {
+            let context = random_context(seed, 12, 8);
+            let basis = ImplicationSet::from_implications(8, context.canonical_basis());
+
+            // Pad the basis with implications that follow from it.
+            let mut next = lcg(seed + 100);
+            let mut padded = basis.clone();
+            for _ in 0..10 {
+                let premise = random_set(&mut next, 8, 40);
+                let conclusion = context.induce_r(&context.induce_l(&premise));
+                padded.push(Implication {
+                    premise,
+                    conclusion,
+                });
+            }
+            assert!(padded.is_equivalent(&basis));
+
+            let cover = padded.minimal_cover();
+            assert_eq!(cover, basis);
+            assert!(cover.holds_in(&context));
+            assert_eq!(cover.minimal_cover(), cover);
+
+            padded.remove_redundant();
+            assert!(padded.is_equivalent(&basis));
+            assert!(padded.len() >= basis.len());
+            for i in 0..padded.len() {
+                let mut others = padded.clone();
+                let removed = others.implications.remove(i);
+                assert!(!others.entails(&removed));
+            }
+        }
+    }
+
+    #[test]
+    fn test_implies_and_equivalence() {
+        let context = FormalContext::new(
+            vec!["g"],
+            vec!["a", "b", "c", "d"],
+            vec![bitvec![0, 0, 0, 0]],
+        );
+        let implications = ImplicationSet::from_implications(
+            4,
+            vec![
+                context.implication(["a"], ["b"]),
+                context.implication(["b", "c"], ["d"]),
+            ],
+        );
+
+        assert!(implications.entails(&context.implication(["a", "c"], ["d"])));
+        assert!(implications.implies(&bitvec![1, 0, 0, 0], &bitvec![1, 1, 0, 0]));
+        assert!(!implications.entails(&context.implication(["a"], ["d"])));
+        assert!(implications.entails(&context.implication(["d"], [])));
+
+        let mut stronger = implications.clone();
+        stronger.push(context.implication(["a"], ["d"]));
+        assert!(!implications.is_equivalent(&stronger));
+        assert!(stronger.is_equivalent(&stronger.minimal_cover()));
+        assert!(
+            implications.is_equivalent(&ImplicationSet::from_implications(
+                4,
+                vec![
+                    context.implication(["a"], ["b"]),
+                    context.implication(["a", "c"], ["b", "d"]),
+                    context.implication(["b", "c"], ["d"]),
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_violations() {
+        let context = FormalContext::new(
+            vec!["sparrow", "penguin", "bat"],
+            vec!["bird", "flies", "mammal"],
+            vec![bitvec![1, 1, 0], bitvec![1, 0, 0], bitvec![0, 1, 1]],
+        );
+        let implications = ImplicationSet::from_implications(
+            3,
+            vec![
+                context.implication(["bird"], ["flies"]),
+                context.implication(["mammal"], ["flies"]),
+                context.implication(["flies"], ["bird"]),
+            ],
+        );
+
+        assert!(!implications.holds_in(&context));
+        let violations = implications.violations(&context);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].implication, 0);
+        assert_eq!(
+            violations[0]
+                .object_names_iter(&context)
+                .collect::<Vec<_>>(),
+            [&"penguin"]
+        );
+        assert_eq!(violations[1].implication, 2);
+        assert_eq!(violations[1].objects, bitvec![0, 0, 1]);
+
+        assert_eq!(
+            implications.display(&context).to_string(),
+            concat!(
+                "[\"bird\"] => [\"flies\"]\n",
+                "[\"mammal\"] => [\"flies\"]\n",
+                "[\"flies\"] => [\"bird\"]\n",
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Attribute not found in context")]
+    fn test_implication_unknown_label() {
+        let context = FormalContext::new(vec!["g"], vec!["a"], vec![bitvec![1]]);
+        context.implication(["b"], ["a"]);
+    }
+}
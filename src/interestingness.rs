@@ -0,0 +1,608 @@
+use crate::FormalConcept;
+use crate::FormalContext;
+use crate::RawFormalConcept;
+use crate::bit_fiddling::*;
+
+use bitvec::prelude::*;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+// Stability and robustness both weigh the subsets of an extent (or intent)
+// that generate the same concept. Writing `A` for the extent, `B` for the
+// intent and `C'` for the common attributes of `C ⊆ A`, robustness is
+//
+//     r(α) = Σ { α^|C| (1 - α)^(|A| - |C|) : C ⊆ A, C' = B },
+//
+// the probability that `B` is still the intent when every object of `A` is
+// kept with probability `α`; intensional stability is `r(1/2)`, the fraction
+// of subsets of `A` with intent `B`. The exact computation is a depth-first
+// search over the objects of `A`. A branch stops as soon as its intent
+// reaches `B` (adding objects cannot change it, so the remaining choices
+// contribute weight 1) or can no longer reach it.
+
+/// Settings for computing the stability and robustness of concepts.
+///
+/// Both are exact sums over all subsets of an extent or intent. For sets with
+/// more than `exact_limit` elements, they are instead estimated from
+/// `samples` random subsets, drawn by a generator seeded with `seed`, so that
+/// estimates are reproducible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StabilityOptions {
+    /// The largest extent (or intent) size for which the exact value is
+    /// computed.
+    pub exact_limit: usize,
+    /// The number of random subsets drawn for an estimate.
+    pub samples: usize,
+    /// The seed of the random number generator used for estimates.
+    pub seed: u64,
+}
+
+impl Default for StabilityOptions {
+    /// Exact values up to 20 elements, and 10,000 samples beyond.
+    fn default() -> Self {
+        Self {
+            exact_limit: 20,
+            samples: 10_000,
+            seed: 0,
+        }
+    }
+}
+
+impl RawFormalConcept {
+    /// Returns the intensional stability of this concept in `context`: the
+    /// fraction of subsets of the extent whose common attributes are exactly
+    /// the intent.
+    ///
+    /// Stable concepts do not depend on a few particular objects, so they are
+    /// less likely to be artifacts of noise. See [`StabilityOptions`] for
+    /// when the value is estimated rather than computed exactly.
+    pub fn intensional_stability<A, B>(
+        &self,
+        context: &FormalContext<A, B>,
+        options: &StabilityOptions,
+    ) -> f64 {
+        self.robustness(context, 0.5, options)
+    }
+
+    /// Returns the extensional stability of this concept in `context`: the
+    /// fraction of subsets of the intent whose common objects are exactly the
+    /// extent.
+    ///
+    /// This is the intensional stability of the concept in the dual context.
+    pub fn extensional_stability<A, B>(
+        &self,
+        context: &FormalContext<A, B>,
+        options: &StabilityOptions,
+    ) -> f64 {
+        let columns: Vec<&BitVec> = self
+            .intent
+            .iter_ones()
+            .map(|j| context.get_attribute_extent(j))
+            .collect();
+        generating_subsets_weight(&columns, &self.extent, 0.5, options)
+    }
+
+    /// Returns the robustness of this concept in `context` for the parameter
+    /// `alpha`: the probability that the intent is still closed, with the
+    /// same objects, when every object of the extent is kept independently
+    /// with probability `alpha`.
+    ///
+    /// For `alpha = 0.5`, this is the
+    /// [intensional stability](RawFormalConcept::intensional_stability).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alpha` is not between 0 and 1.
+    pub fn robustness<A, B>(
+        &self,
+        context: &FormalContext<A, B>,
+        alpha: f64,
+        options: &StabilityOptions,
+    ) -> f64 {
+        assert!(
+            (0.0..=1.0).contains(&alpha),
+            "Robustness parameter must be between 0 and 1"
+        );
+        let rows: Vec<&BitVec> = self
+            .extent
+            .iter_ones()
+            .map(|i| context.get_object_intent(i))
+            .collect();
+        generating_subsets_weight(&rows, &self.intent, alpha, options)
+    }
+
+    /// Returns the separation index of this concept in `context`: the share
+    /// of the crosses in the rows of the extent and the columns of the intent
+    /// that lie in the rectangle covered by the concept.
+    ///
+    /// A value of 1 means that no object of the extent has other attributes
+    /// and no attribute of the intent has other objects. Concepts covering no
+    /// cross have separation 0.
+    pub fn separation<A, B>(&self, context: &FormalContext<A, B>) -> f64 {
+        let area = self.extent.count_ones() * self.intent.count_ones();
+        if area == 0 {
+            return 0.0;
+        }
+        let row_crosses: usize = self
+            .extent
+            .iter_ones()
+            .map(|i| context.get_object_intent(i).count_ones())
+            .sum();
+        let column_crosses: usize = self
+            .intent
+            .iter_ones()
+            .map(|j| context.get_attribute_extent(j).count_ones())
+            .sum();
+        area as f64 / (row_crosses + column_crosses - area) as f64
+    }
+
+    /// Returns the support of this concept: the fraction of all objects that
+    /// are in its extent.
+    ///
+    /// The support of the only concept of a context without objects is 1.
+    pub fn support(&self) -> f64 {
+        if self.extent.is_empty() {
+            return 1.0;
+        }
+        self.extent.count_ones() as f64 / self.extent.len() as f64
+    }
+
+    /// Returns the probability index of this concept in `context`: the
+    /// probability that the intent is closed in a random context of the same
+    /// size in which every object has each attribute independently, with the
+    /// attribute's frequency in `context`.
+    ///
+    /// Unlike the other measures, small values are interesting: an intent
+    /// that is unlikely to be closed by chance reflects structure in the
+    /// data.
+    pub fn probability_index<A, B>(&self, context: &FormalContext<A, B>) -> f64 {
+        let n = context.objects.len();
+        if n == 0 {
+            // The intent of the only concept is everything, which is closed.
+            return if self.intent.all() { 1.0 } else { 0.0 };
+        }
+        let frequency = |j: usize| context.get_attribute_extent(j).count_ones() as f64 / n as f64;
+        let p: f64 = self.intent.iter_ones().map(frequency).product();
+        let others: Vec<f64> = self.intent.iter_zeros().map(frequency).collect();
+        // With `k` objects having every attribute of the intent, it is closed
+        // when each other attribute is missing from at least one of them.
+        binomial_pmf(n, p)
+            .filter(|&(_, probability)| probability > NEGLIGIBLE)
+            .map(|(k, probability)| {
+                let closed: f64 = others.iter().map(|&q| 1.0 - q.powi(k as i32)).product();
+                probability * closed
+            })
+            .sum()
+    }
+}
+
+impl<A, B> FormalConcept<A, B> {
+    /// Returns the intensional stability of this concept.
+    ///
+    /// See [`RawFormalConcept::intensional_stability`] for details.
+    pub fn intensional_stability(&self, options: &StabilityOptions) -> f64 {
+        self.data.intensional_stability(&self.context, options)
+    }
+
+    /// Returns the extensional stability of this concept.
+    ///
+    /// See [`RawFormalConcept::extensional_stability`] for details.
+    pub fn extensional_stability(&self, options: &StabilityOptions) -> f64 {
+        self.data.extensional_stability(&self.context, options)
+    }
+
+    /// Returns the robustness of this concept for the parameter `alpha`.
+    ///
+    /// See [`RawFormalConcept::robustness`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alpha` is not between 0 and 1.
+    pub fn robustness(&self, alpha: f64, options: &StabilityOptions) -> f64 {
+        self.data.robustness(&self.context, alpha, options)
+    }
+
+    /// Returns the separation index of this concept.
+    ///
+    /// See [`RawFormalConcept::separation`] for details.
+    pub fn separation(&self) -> f64 {
+        self.data.separation(&self.context)
+    }
+
+    /// Returns the fraction of all objects that are in this concept's extent.
+    pub fn support(&self) -> f64 {
+        self.data.support()
+    }
+
+    /// Returns the probability index of this concept.
+    ///
+    /// See [`RawFormalConcept::probability_index`] for details.
+    pub fn probability_index(&self) -> f64 {
+        self.data.probability_index(&self.context)
+    }
+}
+
+impl<A: Sync, B: Sync> FormalContext<A, B> {
+    /// Returns the `k` raw concepts with the highest values of `measure`,
+    /// each with its value, from highest to lowest.
+    ///
+    /// Concepts are enumerated lazily, as by
+    /// [`FormalContext::all_concepts_raw_iter`], and at most `k` of them are
+    /// kept at any time, so the whole lattice is never stored. Ties are
+    /// broken in favour of the concept enumerated first. To rank by a measure
+    /// where small values are interesting, such as
+    /// [`RawFormalConcept::probability_index`], negate it.
+    pub fn top_k_concepts_raw_by(
+        &self,
+        measure: impl FnMut(&RawFormalConcept) -> f64,
+        k: usize,
+    ) -> Vec<(RawFormalConcept, f64)> {
+        top_k(self.all_concepts_raw_iter(), measure, k)
+    }
+}
+
+impl<A: Clone + Sync, B: Clone + Sync> FormalContext<A, B> {
+    /// Returns the `k` named concepts with the highest values of `measure`,
+    /// each with its value, from highest to lowest.
+    ///
+    /// See [`FormalContext::top_k_concepts_raw_by`] for details.
+    pub fn top_k_concepts_by(
+        &self,
+        measure: impl FnMut(&FormalConcept<A, B>) -> f64,
+        k: usize,
+    ) -> Vec<(FormalConcept<A, B>, f64)> {
+        self.top_k_concepts_with_min_support_by(0, measure, k)
+    }
+
+    /// Returns the `k` named concepts with at least `min_support` objects
+    /// that have the highest values of `measure`, each with its value, from
+    /// highest to lowest.
+    ///
+    /// Concepts are enumerated lazily, as by
+    /// [`FormalContext::all_concepts_raw_iter_with_min_support`], and ranked
+    /// as by [`FormalContext::top_k_concepts_raw_by`], so neither the lattice
+    /// nor the frequent concepts are ever stored.
+    pub fn top_k_concepts_with_min_support_by(
+        &self,
+        min_support: usize,
+        measure: impl FnMut(&FormalConcept<A, B>) -> f64,
+        k: usize,
+    ) -> Vec<(FormalConcept<A, B>, f64)> {
+        let arc = self.arc();
+        let concepts = self
+            .all_concepts_raw_iter_with_min_support(min_support)
+            .map(|data| FormalConcept {
+                context: arc.clone(),
+                data,
+            });
+        top_k(concepts, measure, k)
+    }
+}
+
+// Terms of the probability index below this are skipped; they cannot change
+// the result noticeably.
+const NEGLIGIBLE: f64 = 1e-18;
+
+// The total weight of the subsets `C` of `rows` whose intersection is
+// `target`, where a subset has weight `alpha^|C| (1 - alpha)^(rows - |C|)`.
+// Every row must contain `target`.
+fn generating_subsets_weight(
+    rows: &[&BitVec],
+    target: &BitVec,
+    alpha: f64,
+    options: &StabilityOptions,
+) -> f64 {
+    if rows.len() > options.exact_limit {
+        return estimate_weight(rows, target, alpha, options);
+    }
+    // `reachable[i]` is the intersection of the rows from `i` on: a branch at
+    // row `i` can only reach `target` if its intent meets it there.
+    let mut reachable = vec![BitVec::repeat(true, target.len()); rows.len() + 1];
+    for i in (0..rows.len()).rev() {
+        reachable[i] = reachable[i + 1].clone();
+        and_assign(&mut reachable[i], rows[i]);
+    }
+    exact_weight(
+        rows,
+        target,
+        alpha,
+        &reachable,
+        0,
+        BitVec::repeat(true, target.len()),
+    )
+}
+
+fn exact_weight(
+    rows: &[&BitVec],
+    target: &BitVec,
+    alpha: f64,
+    reachable: &[BitVec],
+    i: usize,
+    intent: BitVec,
+) -> f64 {
+    if intent == *target {
+        return 1.0;
+    }
+    let mut lowest = intent.clone();
+    and_assign(&mut lowest, &reachable[i]);
+    if i == rows.len() || lowest != *target {
+        return 0.0;
+    }
+    let mut with_row = intent.clone();
+    and_assign(&mut with_row, rows[i]);
+    alpha * exact_weight(rows, target, alpha, reachable, i + 1, with_row)
+        + (1.0 - alpha) * exact_weight(rows, target, alpha, reachable, i + 1, intent)
+}
+
+fn estimate_weight(
+    rows: &[&BitVec],
+    target: &BitVec,
+    alpha: f64,
+    options: &StabilityOptions,
+) -> f64 {
+    if options.samples == 0 {
+        return 0.0;
+    }
+    let mut rng = SplitMix64(options.seed);
+    let hits = (0..options.samples)
+        .filter(|_| {
+            let mut intent = BitVec::repeat(true, target.len());
+            for row in rows {
+                if rng.next_f64() < alpha {
+                    and_assign(&mut intent, row);
+                }
+            }
+            intent == *target
+        })
+        .count();
+    hits as f64 / options.samples as f64
+}
+
+// The probabilities of `k` successes in `n` trials with success probability
+// `p`, for `k` from 0 to `n`.
+fn binomial_pmf(n: usize, p: f64) -> impl Iterator<Item = (usize, f64)> {
+    let (ln_p, ln_q) = (p.ln(), (1.0 - p).ln());
+    let mut ln_choose = 0.0;
+    (0..=n).map(move |k| {
+        if k > 0 {
+            ln_choose += ((n - k + 1) as f64).ln() - (k as f64).ln();
+        }
+        // Avoid `0 * ln(0)` when `p` is 0 or 1.
+        let successes = if k == 0 { 0.0 } else { k as f64 * ln_p };
+        let failures = if k == n { 0.0 } else { (n - k) as f64 * ln_q };
+        (k, (ln_choose + successes + failures).exp())
+    })
+}
+
+// A small, fast generator for reproducible Monte Carlo estimates
+// (Steele, Lea & Flood, "Fast splittable pseudorandom number generators").
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // A uniform sample from [0, 1).
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// An item ranked by score; among equal scores, earlier items rank higher.
+struct Ranked<T> {
+    score: f64,
+    order: usize,
+    item: T,
+}
+
+impl<T> PartialEq for Ranked<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Ranked<T> {}
+
+impl<T> PartialOrd for Ranked<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Ranked<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then(other.order.cmp(&self.order))
+    }
+}
+
+// Keep the `k` items with the highest scores in a min-heap.
+fn top_k<T>(
+    items: impl Iterator<Item = T>,
+    mut measure: impl FnMut(&T) -> f64,
+    k: usize,
+) -> Vec<(T, f64)> {
+    if k == 0 {
+        return Vec::new();
+    }
+    let mut heap = BinaryHeap::new();
+    for (order, item) in items.enumerate() {
+        let score = measure(&item);
+        heap.push(Reverse(Ranked { score, order, item }));
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(ranked)| (ranked.item, ranked.score))
+        .collect()
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> FormalContext<&'static str, &'static str> {
+        FormalContext::new(
+            vec!["g1", "g2", "g3", "g4"],
+            vec!["a", "b", "c"],
+            vec![
+                bitvec![1, 1, 0],
+                bitvec![1, 0, 1],
+                bitvec![1, 1, 1],
+                bitvec![0, 0, 1],
+            ],
+        )
+    }
+
+    fn concept(
+        context: &FormalContext<&'static str, &'static str>,
+        intent: BitVec,
+    ) -> RawFormalConcept {
+        RawFormalConcept {
+            extent: context.induce_l(&intent),
+            intent,
+        }
+    }
+
+    // Count the subsets of the extent whose intent is the concept's intent.
+    fn brute_force_stability(
+        context: &FormalContext<&'static str, &'static str>,
+        concept: &RawFormalConcept,
+    ) -> f64 {
+        let objects: Vec<usize> = concept.extent.iter_ones().collect();
+        let hits = (0..1u32 << objects.len())
+            .filter(|subset| {
+                let mut extent = BitVec::repeat(false, context.objects.len());
+                for (bit, &i) in objects.iter().enumerate() {
+                    extent.set(i, subset >> bit & 1 == 1);
+                }
+                context.induce_r(&extent) == concept.intent
+            })
+            .count();
+        hits as f64 / (1u32 << objects.len()) as f64
+    }
+
+    #[test]
+    fn test_stability_matches_brute_force() {
+        let context = context();
+        let options = StabilityOptions::default();
+        for concept in context.all_concepts_raw() {
+            let stability = concept.intensional_stability(&context, &options);
+            assert!((stability - brute_force_stability(&context, &concept)).abs() < 1e-12);
+            assert_eq!(stability, concept.robustness(&context, 0.5, &options));
+
+            let dual = context.dual();
+            let dual_concept = RawFormalConcept {
+                extent: concept.intent.clone(),
+                intent: concept.extent.clone(),
+            };
+            assert_eq!(
+                concept.extensional_stability(&context, &options),
+                dual_concept.intensional_stability(&dual, &options)
+            );
+        }
+
+        // {a}: extent {g1, g2, g3}; the subsets with intent exactly {a} are
+        // {g1, g2} and {g1, g2, g3}.
+        let a = concept(&context, bitvec![1, 0, 0]);
+        assert_eq!(a.intensional_stability(&context, &options), 2.0 / 8.0);
+        assert_eq!(a.robustness(&context, 1.0, &options), 1.0);
+        assert_eq!(a.robustness(&context, 0.0, &options), 0.0);
+    }
+
+    #[test]
+    fn test_stability_estimate() {
+        let context = context();
+        let top = concept(&context, bitvec![0, 0, 0]);
+        let exact = top.intensional_stability(&context, &StabilityOptions::default());
+        let options = StabilityOptions {
+            exact_limit: 0,
+            samples: 20_000,
+            seed: 7,
+        };
+
+        let estimate = top.intensional_stability(&context, &options);
+
+        assert!((estimate - exact).abs() < 0.02);
+        assert_eq!(estimate, top.intensional_stability(&context, &options));
+    }
+
+    #[test]
+    fn test_separation_support_and_probability() {
+        let context = context();
+        let ab = concept(&context, bitvec![1, 1, 0]);
+
+        // Extent {g1, g3}: 2 + 3 crosses in the rows, 3 + 2 in the columns.
+        assert_eq!(ab.separation(&context), 4.0 / 6.0);
+        assert_eq!(ab.support(), 0.5);
+
+        // P(a and b) = 3/4 * 2/4; c (frequency 3/4) must be missing from one
+        // of the k objects having both.
+        let p: f64 = 0.375;
+        let expected: f64 = (0..=4)
+            .map(|k| {
+                let choose = [1.0, 4.0, 6.0, 4.0, 1.0][k];
+                choose
+                    * p.powi(k as i32)
+                    * (1.0 - p).powi(4 - k as i32)
+                    * (1.0 - 0.75f64.powi(k as i32))
+            })
+            .sum();
+        assert!((ab.probability_index(&context) - expected).abs() < 1e-12);
+
+        // Every attribute set is closed.
+        let bottom = concept(&context, bitvec![1, 1, 1]);
+        assert!((bottom.probability_index(&context) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_top_k_concepts() {
+        let context = context();
+        let options = StabilityOptions::default();
+        let mut all: Vec<f64> = context
+            .all_concepts_raw()
+            .iter()
+            .map(|concept| concept.intensional_stability(&context, &options))
+            .collect();
+        all.sort_by(|a, b| b.total_cmp(a));
+
+        let top = context.top_k_concepts_by(|concept| concept.intensional_stability(&options), 3);
+
+        assert_eq!(top.len(), 3);
+        let scores: Vec<f64> = top.iter().map(|(_, score)| *score).collect();
+        assert_eq!(scores, all[..3]);
+        for (concept, score) in &top {
+            assert_eq!(concept.intensional_stability(&options), *score);
+        }
+        assert_eq!(
+            context
+                .top_k_concepts_raw_by(|concept| concept.support(), 100)
+                .len(),
+            context.num_concepts()
+        );
+        assert!(context.top_k_concepts_raw_by(|_| 0.0, 0).is_empty());
+
+        // Only concepts with at least 3 objects are ranked.
+        let frequent = context.top_k_concepts_with_min_support_by(
+            3,
+            |concept| concept.data.intent.count_ones() as f64,
+            100,
+        );
+        assert_eq!(frequent.len(), context.num_concepts_with_min_support(3));
+        assert!(
+            frequent
+                .iter()
+                .all(|(concept, _)| concept.data.extent.count_ones() >= 3)
+        );
+        assert!(frequent.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
+}
@@ -0,0 +1,437 @@
+use crate::ConceptLattice;
+use crate::LatticeLabels;
+use crate::xml::escape_xml;
+
+use std::fmt::Display;
+use std::io::{self, Write};
+
+// Line diagrams are laid out in two steps.
+//
+// Vertically, concepts are layered by the length of the longest chain from
+// the top concept, so every cover edge points upwards.
+//
+// Horizontally, the layout is additive, as in ConExp: every meet-irreducible
+// concept `m` gets a horizontal offset `v(m)`, and a concept is placed at the
+// sum of the offsets of the meet-irreducibles above it. Meet-irreducibles are
+// attribute concepts, so a concept is below `m` exactly when its intent
+// contains an attribute of `m`. Additive diagrams make the lattice structure
+// easy to read, since parallel edges stand for the same attributes.
+//
+// The offsets are a permutation of evenly spaced values, and crossings are
+// minimised by hill climbing over that permutation with adjacent swaps. After
+// each change, nodes that would overlap within a layer are pushed apart.
+
+// Counting crossings compares every pair of edges, so the optimisation is
+// skipped for diagrams with more edges than this; they are unreadable anyway.
+const MAX_OPTIMISED_EDGES: usize = 400;
+
+// Upper bound on the number of passes of adjacent swaps.
+const MAX_OPTIMISATION_ROUNDS: usize = 8;
+
+// Minimum horizontal distance between two nodes of the same layer.
+const MIN_NODE_GAP: f64 = 1.0;
+
+// Scale of the SVG output, in pixels per layout unit.
+const SVG_UNIT_X: f64 = 60.0;
+const SVG_UNIT_Y: f64 = 80.0;
+
+// Rough width of a character of the SVG label font, used to leave room for
+// labels at the edges of the drawing.
+const SVG_CHAR_WIDTH: f64 = 7.0;
+
+/// Coordinates of the nodes of a [`ConceptLattice`] line diagram; see
+/// [`ConceptLattice::layout`].
+///
+/// Positions are indexed like the concepts of the lattice. The `x` coordinate
+/// grows to the right and starts at `0`; the `y` coordinate grows downwards
+/// and is the layer of the concept, so the top concept has `y == 0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatticeLayout {
+    positions: Vec<(f64, f64)>,
+}
+
+impl LatticeLayout {
+    /// Returns the position of the i-th concept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn position(&self, i: usize) -> (f64, f64) {
+        self.positions[i]
+    }
+
+    /// Returns the positions of all concepts, indexed like the concepts of the
+    /// lattice.
+    pub fn positions(&self) -> &[(f64, f64)] {
+        &self.positions
+    }
+
+    /// Returns the number of pairs of cover edges of `lattice` that cross in
+    /// this layout. Edges that share an endpoint do not count as crossing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lattice` has a different number of concepts than this
+    /// layout.
+    pub fn crossings<A, B>(&self, lattice: &ConceptLattice<A, B>) -> usize {
+        assert_eq!(self.positions.len(), lattice.num_concepts());
+        let edges: Vec<(usize, usize)> = lattice.covers_iter().collect();
+        count_crossings(&self.positions, &edges)
+    }
+}
+
+impl<A, B> ConceptLattice<A, B> {
+    /// Computes a layered, additive layout of the line diagram of this
+    /// lattice.
+    ///
+    /// Every concept is placed one layer below the lowest of its upper
+    /// covers. Horizontally, each meet-irreducible concept contributes a fixed
+    /// offset to all concepts below it, and the offsets are chosen to reduce
+    /// the number of crossing edges. Nodes in the same layer are kept at least
+    /// one unit apart. The result is deterministic.
+    pub fn layout(&self) -> LatticeLayout {
+        let layers = self.layers();
+        let edges: Vec<(usize, usize)> = self.covers_iter().collect();
+
+        // One representative attribute per meet-irreducible concept.
+        let irreducibles: Vec<usize> = (0..self.num_concepts())
+            .filter(|&i| self.upper_covers(i).len() == 1)
+            .filter_map(|i| {
+                let intent = &self.raw_concept(i).intent;
+                let parent = &self.raw_concept(self.upper_covers(i)[0]).intent;
+                (intent.clone() & !parent.clone()).first_one()
+            })
+            .collect();
+
+        let place = |order: &[usize]| {
+            let center = (order.len() as f64 - 1.0) / 2.0;
+            let raw: Vec<f64> = self
+                .concepts()
+                .iter()
+                .map(|concept| {
+                    order
+                        .iter()
+                        .enumerate()
+                        .filter(|&(_, &j)| concept.intent[j])
+                        .map(|(p, _)| p as f64 - center)
+                        .sum()
+                })
+                .collect();
+            spread_layers(&raw, &layers)
+        };
+
+        let mut order = irreducibles;
+        let mut positions = place(&order);
+        if edges.len() <= MAX_OPTIMISED_EDGES {
+            let mut crossings = count_crossings(&positions, &edges);
+            for _ in 0..MAX_OPTIMISATION_ROUNDS {
+                let mut improved = false;
+                for p in 1..order.len() {
+                    if crossings == 0 {
+                        break;
+                    }
+                    order.swap(p - 1, p);
+                    let candidate = place(&order);
+                    let candidate_crossings = count_crossings(&candidate, &edges);
+                    if candidate_crossings < crossings {
+                        positions = candidate;
+                        crossings = candidate_crossings;
+                        improved = true;
+                    } else {
+                        order.swap(p - 1, p);
+                    }
+                }
+                if !improved {
+                    break;
+                }
+            }
+        }
+
+        LatticeLayout { positions }
+    }
+
+    // The layer of each concept: the length of the longest chain from the top
+    // concept. Upper covers have larger extents, so one pass in order of
+    // decreasing extent size suffices, whatever the index order.
+    fn layers(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.num_concepts()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(self.raw_concept(i).extent.count_ones()));
+        let mut layers = vec![0; self.num_concepts()];
+        for i in order {
+            layers[i] = self
+                .upper_covers(i)
+                .iter()
+                .map(|&upper| layers[upper] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+        layers
+    }
+}
+
+impl<A: Display, B: Display> ConceptLattice<A, B> {
+    /// Writes the line diagram of this lattice as a standalone SVG image,
+    /// using [`ConceptLattice::layout`] to place the concepts.
+    ///
+    /// Attribute labels are drawn above their node and object labels below
+    /// it, as selected by `labels`. Unlike [`ConceptLattice::write_dot`], no
+    /// external tool is needed to render the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error produced by `output`.
+    pub fn write_svg(&self, mut output: impl Write, labels: LatticeLabels) -> io::Result<()> {
+        let layout = self.layout();
+        let labels = self.node_labels(labels);
+
+        let longest_label = labels
+            .iter()
+            .flat_map(|(attributes, objects)| [attributes, objects])
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let margin_x = (longest_label as f64 * SVG_CHAR_WIDTH / 2.0 + 10.0).max(40.0);
+        let margin_y = 40.0;
+        let (max_x, max_y) = layout
+            .positions()
+            .iter()
+            .fold((0.0_f64, 0.0_f64), |(mx, my), &(x, y)| {
+                (mx.max(x), my.max(y))
+            });
+        let width = max_x * SVG_UNIT_X + 2.0 * margin_x;
+        let height = max_y * SVG_UNIT_Y + 2.0 * margin_y;
+        let point = |i: usize| {
+            let (x, y) = layout.position(i);
+            (x * SVG_UNIT_X + margin_x, y * SVG_UNIT_Y + margin_y)
+        };
+
+        writeln!(
+            output,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.1}" height="{height:.1}" viewBox="0 0 {width:.1} {height:.1}" font-family="sans-serif" font-size="12">"#
+        )?;
+        writeln!(output, r#"  <g stroke="black" stroke-width="1">"#)?;
+        for (lower, upper) in self.covers_iter() {
+            let (x1, y1) = point(lower);
+            let (x2, y2) = point(upper);
+            writeln!(
+                output,
+                r#"    <line x1="{x1:.1}" y1="{y1:.1}" x2="{x2:.1}" y2="{y2:.1}"/>"#
+            )?;
+        }
+        writeln!(output, "  </g>")?;
+        writeln!(
+            output,
+            r#"  <g fill="white" stroke="black" stroke-width="1">"#
+        )?;
+        for i in 0..self.num_concepts() {
+            let (x, y) = point(i);
+            writeln!(output, r#"    <circle cx="{x:.1}" cy="{y:.1}" r="6"/>"#)?;
+        }
+        writeln!(output, "  </g>")?;
+        writeln!(output, r#"  <g text-anchor="middle">"#)?;
+        for (i, (attributes, objects)) in labels.iter().enumerate() {
+            let (x, y) = point(i);
+            if !attributes.is_empty() {
+                writeln!(
+                    output,
+                    r#"    <text x="{x:.1}" y="{:.1}">{}</text>"#,
+                    y - 10.0,
+                    escape_xml(attributes)
+                )?;
+            }
+            if !objects.is_empty() {
+                writeln!(
+                    output,
+                    r#"    <text x="{x:.1}" y="{:.1}">{}</text>"#,
+                    y + 20.0,
+                    escape_xml(objects)
+                )?;
+            }
+        }
+        writeln!(output, "  </g>")?;
+        writeln!(output, "</svg>")?;
+        output.flush()
+    }
+}
+
+// Turn raw additive x coordinates into final positions: within each layer,
+// nodes keep their left-to-right order but are pushed apart to at least
+// `MIN_NODE_GAP`, and the layer is then shifted back to its original mean.
+// Finally, everything is translated so that the smallest x is 0.
+fn spread_layers(raw: &[f64], layers: &[usize]) -> Vec<(f64, f64)> {
+    let layers_len = layers.iter().max().map_or(0, |&max| max + 1);
+    let mut members = vec![Vec::new(); layers_len];
+    for (i, &layer) in layers.iter().enumerate() {
+        members[layer].push(i);
+    }
+
+    let mut x = raw.to_vec();
+    for layer in &mut members {
+        layer.sort_by(|&a, &b| raw[a].total_cmp(&raw[b]).then(a.cmp(&b)));
+        for k in 1..layer.len() {
+            x[layer[k]] = x[layer[k]].max(x[layer[k - 1]] + MIN_NODE_GAP);
+        }
+        let shift = layer.iter().map(|&i| x[i] - raw[i]).sum::<f64>() / layer.len() as f64;
+        for &i in layer.iter() {
+            x[i] -= shift;
+        }
+    }
+
+    let min_x = x.iter().copied().fold(f64::INFINITY, f64::min);
+    x.iter()
+        .zip(layers)
+        .map(|(&x, &layer)| (x - min_x, layer as f64))
+        .collect()
+}
+
+// Count the pairs of edges whose segments cross, ignoring pairs that share
+// an endpoint.
+fn count_crossings(positions: &[(f64, f64)], edges: &[(usize, usize)]) -> usize {
+    let mut crossings = 0;
+    for (k, &(a, b)) in edges.iter().enumerate() {
+        for &(c, d) in &edges[k + 1..] {
+            if a == c || a == d || b == c || b == d {
+                continue;
+            }
+            if segments_cross(positions[a], positions[b], positions[c], positions[d]) {
+                crossings += 1;
+            }
+        }
+    }
+    crossings
+}
+
+// Whether the open segments `pq` and `rs` intersect in a single point.
+fn segments_cross(p: (f64, f64), q: (f64, f64), r: (f64, f64), s: (f64, f64)) -> bool {
+    const EPSILON: f64 = 1e-9;
+    let orientation = |a: (f64, f64), b: (f64, f64), c: (f64, f64)| {
+        let cross = (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+        if cross > EPSILON {
+            1
+        } else if cross < -EPSILON {
+            -1
+        } else {
+            0
+        }
+    };
+    let (o1, o2) = (orientation(p, q, r), orientation(p, q, s));
+    let (o3, o4) = (orientation(r, s, p), orientation(r, s, q));
+    o1 * o2 < 0 && o3 * o4 < 0
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FormalContext;
+    use crate::test_support::lives_in_water;
+    use bitvec::prelude::*;
+
+    #[test]
+    fn test_layout_is_layered() {
+        let lattice = lives_in_water().concept_lattice();
+        let layout = lattice.layout();
+
+        assert_eq!(layout.positions().len(), lattice.num_concepts());
+        assert_eq!(layout.position(lattice.top()).1, 0.0);
+        for (lower, upper) in lattice.covers_iter() {
+            assert!(layout.position(upper).1 < layout.position(lower).1);
+        }
+        // Nodes of the same layer never overlap.
+        let positions = layout.positions();
+        for a in 0..positions.len() {
+            for b in a + 1..positions.len() {
+                if positions[a].1 == positions[b].1 {
+                    assert!((positions[a].0 - positions[b].0).abs() >= MIN_NODE_GAP - 1e-9);
+                }
+            }
+        }
+        assert!(positions.iter().all(|&(x, _)| x >= 0.0));
+        assert!(positions.iter().any(|&(x, _)| x == 0.0));
+    }
+
+    #[test]
+    fn test_layout_of_grid_has_no_crossings() {
+        // The product of a 3-chain and a 4-chain is planar. Object `(i, j)`
+        // has the first `i` attributes of one chain and the first `j` of the
+        // other; the attributes are interleaved so that the initial layout
+        // has crossings.
+        let mut relation = Vec::new();
+        for i in 0..3 {
+            for j in 0..4 {
+                relation.push(
+                    (0..5)
+                        .map(|k| if k % 2 == 1 { k / 2 < i } else { k / 2 < j })
+                        .collect::<BitVec>(),
+                );
+            }
+        }
+        let context = FormalContext::new((0..12).collect(), (0..5).collect(), relation);
+        let lattice: ConceptLattice<usize, usize> = context.concept_lattice();
+
+        let layout = lattice.layout();
+
+        assert_eq!(lattice.num_concepts(), 12);
+        assert_eq!(layout.crossings(&lattice), 0);
+        assert_eq!(layout, lattice.layout());
+    }
+
+    #[test]
+    fn test_layout_of_boolean_lattice() {
+        let context = FormalContext::contranomial_scale((0..3).collect::<Vec<_>>());
+        let lattice = context.concept_lattice();
+
+        let layout = lattice.layout();
+
+        // The usual drawing of the cube, with three nodes in each middle layer.
+        assert_eq!(layout.crossings(&lattice), 2);
+        assert_eq!(layout.position(lattice.top()), (1.0, 0.0));
+        assert_eq!(layout.position(lattice.bottom()), (1.0, 3.0));
+    }
+
+    #[test]
+    fn test_segments_cross() {
+        assert!(segments_cross(
+            (0.0, 0.0),
+            (2.0, 2.0),
+            (0.0, 2.0),
+            (2.0, 0.0)
+        ));
+        assert!(!segments_cross(
+            (0.0, 0.0),
+            (1.0, 1.0),
+            (2.0, 0.0),
+            (3.0, 1.0)
+        ));
+        // Touching at an endpoint is not a crossing.
+        assert!(!segments_cross(
+            (0.0, 0.0),
+            (1.0, 1.0),
+            (1.0, 1.0),
+            (2.0, 0.0)
+        ));
+    }
+
+    #[test]
+    fn test_write_svg() {
+        let context = FormalContext::new(
+            vec!["x", "y"],
+            vec!["a<b", "c"],
+            vec![bitvec![1, 0], bitvec![1, 1]],
+        );
+        let lattice = context.concept_lattice();
+
+        let mut output = Vec::new();
+        lattice
+            .write_svg(&mut output, LatticeLabels::Reduced)
+            .unwrap();
+        let svg = String::from_utf8(output).unwrap();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<circle").count(), lattice.num_concepts());
+        assert_eq!(svg.matches("<line").count(), lattice.covers_iter().count());
+        assert!(svg.contains(">a&lt;b</text>"));
+        assert!(svg.contains(">y</text>"));
+    }
+}
@@ -6,11 +6,69 @@
 //!
 //! 1. Build or load a [`FormalContext`].
 //! 2. Use [`FormalContext::num_concepts`] when only the number of concepts is needed.
-//! 3. Use [`FormalContext::all_concepts`] to enumerate [`FormalConcept`]s.
+//! 3. Use [`FormalContext::all_concepts`] to enumerate [`FormalConcept`]s, or
+//!    [`FormalContext::concepts_iter`] to enumerate them lazily on the
+//!    current thread, or [`FormalContext::lectic_concepts_iter`] to enumerate
+//!    them lazily in a canonical (lectic) order. Concepts are enumerated with
+//!    PCbO by default; [`FormalContext::all_concepts_raw_with_algorithm`]
+//!    selects FCbO or In-Close instead (see [`Algorithm`]).
+//! 4. Use [`FormalContext::concept_lattice`] when the order between concepts
+//!    is needed: a [`ConceptLattice`] stores the concepts together with their
+//!    cover relation. Its line diagram can be exported with
+//!    [`ConceptLattice::write_dot`] for Graphviz, or laid out with
+//!    [`ConceptLattice::layout`] and drawn with [`ConceptLattice::write_svg`].
+//!    When objects arrive one at a time, [`ConceptLattice::add_object`] and
+//!    [`ConceptLattice::remove_object`] update a lattice in place instead of
+//!    recomputing it.
+//!
+//! Attribute dependencies are described by [`Implication`]s;
+//! [`FormalContext::canonical_basis`] computes the canonical
+//! (Duquenne–Guigues) basis of all implications valid in a context, and
+//! [`FormalContext::luxenburger_basis`] computes [`AssociationRule`]s, the
+//! partial implications between frequent concepts. When a context is only
+//! a sample of its domain, [`FormalContext::explore`] runs attribute
+//! exploration: an [`Expert`] confirms each implication or supplies a
+//! counterexample, which is added to the context. Implications from other
+//! sources are collected in an [`ImplicationSet`], which computes closures,
+//! decides entailment, finds a [`ImplicationSet::minimal_cover`] and reports
+//! the objects of a context that violate it; [`FormalContext::implication`]
+//! builds implications from attribute labels.
+//!
+//! Closure systems without an explicit context implement the
+//! [`ClosureOperator`] trait, whose closed sets are enumerated by the same
+//! parallel Close-by-One search used for concepts. [`FormalContext`] is one
+//! such operator, and an implication set another.
 //!
 //! Contexts can be constructed directly with [`FormalContext::new`], loaded from
 //! Burmeister `.cxt` input with [`FormalContext::from_cxt`], or loaded from
-//! simple space-separated `.dat` input with [`FormalContext::from_dat`].
+//! simple space-separated `.dat` input with [`FormalContext::from_dat`]. The
+//! loaders panic on malformed input; [`FormalContext::try_from_cxt`] and
+//! [`FormalContext::try_from_dat`] report a [`ParseError`] instead. Contexts
+//! can be written back out with [`FormalContext::write_cxt`] and
+//! [`FormalContext::write_dat`]. Cross tables exported from spreadsheets are
+//! read with [`FormalContext::try_from_csv`] and written with
+//! [`FormalContext::write_csv`], configured by [`CsvOptions`]. ConExp
+//! `.cex` files are read with [`FormalContext::try_from_cex`] and written with
+//! [`FormalContext::write_cex`].
+//!
+//! Contexts are combined with [`FormalContext::apposition`],
+//! [`FormalContext::subposition`], [`FormalContext::direct_sum`],
+//! [`FormalContext::direct_product`] and [`FormalContext::semiproduct`], which
+//! report incompatible labels as a [`ContextError`], and transformed with
+//! [`FormalContext::transpose`] and [`FormalContext::complement`].
+//! [`FormalContext::clarify`] and [`FormalContext::reduce_with_mapping`]
+//! shrink a context without changing its concept lattice, and report which
+//! objects and attributes were merged or removed.
+//!
+//! Concepts are ranked by interestingness measures such as
+//! [`FormalConcept::intensional_stability`], [`FormalConcept::separation`] and
+//! [`FormalConcept::probability_index`]; [`FormalContext::top_k_concepts_by`]
+//! keeps only the best ones while enumerating.
+//!
+//! Tables with categorical or numeric columns are represented by a
+//! [`ManyValuedContext`], which [`ManyValuedContext::scale`] turns into a
+//! [`FormalContext`] using scales such as [`Scale::nominal`] and
+//! [`Scale::ordinal`].
 //!
 //! [`FormalConcept`] is the ergonomic concept type: it keeps an `Arc` pointer to
 //! its context and can iterate over object and attribute labels.
@@ -22,16 +80,58 @@
 //! The optional `random` feature adds constructors for random contexts:
 //! you can specify the number of objects and attributes, and the desired
 //! (expected) density of the context.
+//!
+//! The optional `serde` feature implements `Serialize` and `Deserialize` for
+//! [`FormalContext`] and [`RawFormalConcept`], and `Serialize` for
+//! [`FormalConcept`], which is written with the labels of its extent and
+//! intent. Bitsets are written as strings of `0`s and `1`s.
 
+mod algorithm;
+mod arrow_relations;
+mod association_rule;
 mod bit_fiddling;
+mod canonical_basis;
+mod cex;
+mod closure_operator;
+mod concept_lattice;
+mod context_algebra;
+mod csv;
+mod error;
+mod exploration;
 mod formal_concept;
 mod formal_context;
+mod implication;
+mod implication_set;
+mod interestingness;
+mod layout;
+mod many_valued;
+mod next_closure;
 mod pcbo;
 #[cfg(feature = "random")]
 mod random;
+mod reduction;
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(test)]
+mod test_support;
+mod xml;
 
+pub use algorithm::*;
+pub use arrow_relations::*;
+pub use association_rule::*;
+pub use closure_operator::*;
+pub use concept_lattice::*;
+pub use csv::*;
+pub use error::*;
+pub use exploration::*;
 pub use formal_concept::*;
 pub use formal_context::*;
+pub use implication::*;
+pub use implication_set::*;
+pub use interestingness::*;
+pub use layout::*;
+pub use many_valued::*;
+pub use reduction::*;
 
 // Tests
 #[cfg(test)]
@@ -93,6 +193,102 @@ mod tests {
         assert!(concepts.iter().all(FormalConcept::validate));
     }
 
+    #[test]
+    fn test_pcbo_sequential_iter() {
+        for objects_len in [20, 130] {
+            let context = FormalContext::new(
+                (0..objects_len).collect(),
+                (0..12).collect(),
+                (0..objects_len)
+                    .map(|g| (0..12).map(|m| (g * 5 + m * 7) % 4 != 0).collect())
+                    .collect(),
+            );
+            let mut expected: Vec<BitVec> = context
+                .all_concepts_raw()
+                .into_iter()
+                .map(|concept| concept.intent)
+                .collect();
+            let mut actual: Vec<BitVec> = context
+                .all_concepts_raw_iter()
+                .map(|concept| concept.intent)
+                .collect();
+            expected.sort();
+            actual.sort();
+            assert_eq!(actual, expected);
+            assert_eq!(context.concepts_iter().take(3).count(), 3);
+            assert!(context.concepts_iter().all(|c| c.validate()));
+            assert_eq!(
+                context.concepts_iter().count(),
+                context.all_concepts_iter().count()
+            );
+        }
+    }
+
+    #[test]
+    fn test_pcbo_min_support() {
+        for objects_len in [20, 130] {
+            let context = FormalContext::new(
+                (0..objects_len).collect(),
+                (0..12).collect(),
+                (0..objects_len)
+                    .map(|g| (0..12).map(|m| (g * 5 + m * 7) % 4 != 0).collect())
+                    .collect(),
+            );
+            let all = context.all_concepts_raw();
+            for min_support in [0, 1, 3, objects_len / 2, objects_len, objects_len + 1] {
+                let mut expected: Vec<BitVec> = all
+                    .iter()
+                    .filter(|concept| concept.extent.count_ones() >= min_support)
+                    .map(|concept| concept.intent.clone())
+                    .collect();
+                let mut actual: Vec<BitVec> = context
+                    .all_concepts_raw_with_min_support(min_support)
+                    .into_iter()
+                    .map(|concept| concept.intent)
+                    .collect();
+                expected.sort();
+                actual.sort();
+                assert_eq!(actual, expected);
+                assert_eq!(
+                    context.num_concepts_with_min_support(min_support),
+                    expected.len()
+                );
+                let mut lazy: Vec<BitVec> = context
+                    .all_concepts_raw_iter_with_min_support(min_support)
+                    .map(|concept| concept.intent)
+                    .collect();
+                lazy.sort();
+                assert_eq!(lazy, expected);
+            }
+            assert_eq!(
+                context.concepts_with_min_relative_support(0.5).len(),
+                context.concepts_with_min_support(objects_len / 2).len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_min_relative_support_at_exact_boundaries() {
+        // `0.07 * 100.0` and `0.28 * 25.0` both evaluate to 7.000000000000001.
+        for (fraction, objects_len) in [(0.07, 100), (0.28, 25)] {
+            let relation = (0..objects_len)
+                .map(|i| std::iter::once(i < 7).collect())
+                .collect();
+            let context = FormalContext::new((0..objects_len).collect(), vec![0], relation);
+
+            assert_eq!(context.min_support_count(fraction), 7);
+            // The concept with exactly the 7 objects of attribute 0 is kept.
+            assert_eq!(
+                context.concepts_with_min_relative_support(fraction).len(),
+                2
+            );
+        }
+        let context = FormalContext::<usize, usize>::zero_context((0..10).collect(), Vec::new());
+        assert_eq!(context.min_support_count(0.0), 0);
+        assert_eq!(context.min_support_count(0.71), 8);
+        assert_eq!(context.min_support_count(1.0), 10);
+    }
+
     #[test]
     fn test_pcbo_dense_path() {
         let context = FormalContext::zero_context((0..129).collect(), (0..129).collect());
@@ -0,0 +1,438 @@
+use crate::FormalContext;
+use crate::ScaleError;
+
+use bitvec::prelude::*;
+use std::fmt::Display;
+use std::sync::Arc;
+
+/// A many-valued context: a table in which every object has, for each
+/// attribute, a value or nothing.
+///
+/// Many-valued contexts are analysed by *conceptual scaling*: every
+/// many-valued attribute is interpreted through a scale, a formal context
+/// [`Scale`] that assigns every value a set of attributes, and
+/// [`ManyValuedContext::scale`] derives an ordinary [`FormalContext`]. Scales
+/// for the usual cases are built by [`Scale::nominal`], [`Scale::ordinal`],
+/// [`Scale::interordinal`], [`Scale::biordinal`] and [`Scale::dichotomic`],
+/// and any scale context can serve as a scale through
+/// [`Scale::from_context`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManyValuedContext<V> {
+    /// Object labels.
+    pub objects: Vec<String>,
+    /// Many-valued attribute labels.
+    pub attributes: Vec<String>,
+    values: Vec<Vec<Option<V>>>, // The values of each object
+}
+
+impl<V> ManyValuedContext<V> {
+    /// Constructs a new many-valued context.
+    ///
+    /// `values[i][j]` is the value of attribute `attributes[j]` for object
+    /// `objects[i]`, or `None` if it is missing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values.len() != objects.len()` or if any row length differs
+    /// from `attributes.len()`.
+    pub fn new(objects: Vec<String>, attributes: Vec<String>, values: Vec<Vec<Option<V>>>) -> Self {
+        assert_eq!(values.len(), objects.len());
+        for row in &values {
+            assert_eq!(row.len(), attributes.len());
+        }
+        Self {
+            objects,
+            attributes,
+            values,
+        }
+    }
+
+    /// Returns the value of attribute `j` for object `i`, if there is one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn value(&self, i: usize, j: usize) -> Option<&V> {
+        self.values[i][j].as_ref()
+    }
+
+    /// Sets the value of attribute `j` for object `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn set_value(&mut self, i: usize, j: usize, value: Option<V>) {
+        self.values[i][j] = value;
+    }
+}
+
+impl<V: Clone + PartialEq> ManyValuedContext<V> {
+    /// Returns the distinct values that attribute `j` takes, in order of first
+    /// appearance. This is a convenient set of values for a nominal scale.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `j` is out of bounds.
+    pub fn attribute_values(&self, j: usize) -> Vec<V> {
+        let mut values: Vec<V> = Vec::new();
+        for value in self.values.iter().filter_map(|row| row[j].as_ref()) {
+            if !values.contains(value) {
+                values.push(value.clone());
+            }
+        }
+        values
+    }
+}
+
+impl<V> ManyValuedContext<V> {
+    /// Derives a formal context by plain scaling, interpreting attribute `j`
+    /// through the scale `scales[j]`.
+    ///
+    /// The derived context has the same objects. Its attributes are the
+    /// attributes of the scales, in order, with each label prefixed by the
+    /// name of its many-valued attribute: an attribute `age` scaled by
+    /// [`Scale::ordinal`] with threshold `30` yields an attribute `age<=30`.
+    /// An object has a derived attribute when [`Scale::intent`] of its value
+    /// contains the corresponding scale attribute; objects with a missing
+    /// value have none of the attributes of that scale.
+    ///
+    /// Returns a [`ScaleError`] for the first value that its scale cannot
+    /// place, which only happens with scales built by [`Scale::from_context`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scales.len()` differs from the number of attributes.
+    pub fn scale(&self, scales: &[Scale<V>]) -> Result<FormalContext<String, String>, ScaleError> {
+        assert_eq!(scales.len(), self.attributes.len());
+        let attributes = self
+            .attributes
+            .iter()
+            .zip(scales)
+            .flat_map(|(attribute, scale)| {
+                scale
+                    .attributes
+                    .iter()
+                    .map(move |scale_attribute| format!("{attribute}{scale_attribute}"))
+            })
+            .collect();
+        let mut relation = Vec::with_capacity(self.objects.len());
+        for (i, row) in self.values.iter().enumerate() {
+            let mut intent = BitVec::new();
+            for (j, (value, scale)) in row.iter().zip(scales).enumerate() {
+                match value {
+                    Some(value) => {
+                        let scaled = scale.intent(value).ok_or(ScaleError {
+                            object: i,
+                            attribute: j,
+                        })?;
+                        intent.extend_from_bitslice(&scaled);
+                    }
+                    None => intent.resize(intent.len() + scale.attributes.len(), false),
+                }
+            }
+            relation.push(intent);
+        }
+        Ok(FormalContext::new(
+            self.objects.clone(),
+            attributes,
+            relation,
+        ))
+    }
+}
+
+/// A conceptual scale: a list of attributes and a rule that assigns every
+/// value its intent, the set of attributes it has.
+///
+/// Threshold scales such as [`Scale::ordinal`] place values by comparing
+/// them with their thresholds, so any value can be scaled, not only the
+/// thresholds themselves. [`Scale::new`] builds a scale from an arbitrary
+/// predicate, and [`Scale::from_context`] uses a scale context, such as
+/// [`FormalContext::contranomial_scale`], whose objects are the values.
+pub struct Scale<V> {
+    attributes: Vec<String>,
+    intent: Arc<ScaleFn<V>>,
+}
+
+// Maps a value to its intent, or to `None` if the scale cannot place it.
+type ScaleFn<V> = dyn Fn(&V) -> Option<BitVec> + Send + Sync;
+
+impl<V> Scale<V> {
+    /// Creates a scale with the given attribute labels, where a value has
+    /// attribute `k` when `has(value, k)` holds.
+    pub fn new(
+        attributes: Vec<String>,
+        has: impl Fn(&V, usize) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        let len = attributes.len();
+        Self {
+            attributes,
+            intent: Arc::new(move |value| Some((0..len).map(|k| has(value, k)).collect())),
+        }
+    }
+
+    /// Returns the attribute labels of this scale.
+    pub fn attributes(&self) -> &[String] {
+        &self.attributes
+    }
+
+    /// Returns the intent of `value`, a bitset over [`Scale::attributes`], or
+    /// `None` if the scale cannot place it.
+    pub fn intent(&self, value: &V) -> Option<BitVec> {
+        (self.intent)(value)
+    }
+}
+
+impl<V: PartialEq + Send + Sync + 'static> Scale<V> {
+    /// Creates a scale from a scale context whose objects are values.
+    ///
+    /// A value has the attributes of the first equal object of `context`.
+    /// Values that are not objects of the context cannot be placed.
+    pub fn from_context(context: FormalContext<V, String>) -> Self {
+        let attributes = context.attributes.clone();
+        Self {
+            attributes,
+            intent: Arc::new(move |value| {
+                let i = context.objects.iter().position(|object| object == value)?;
+                Some(context.get_object_intent(i).clone())
+            }),
+        }
+    }
+}
+
+impl<V: Display + PartialEq + Send + Sync + 'static> Scale<V> {
+    /// Creates the nominal scale on the given values.
+    ///
+    /// There is one attribute `=v` per value `v`, and every value has
+    /// exactly the attribute it equals. Nominal scales suit categorical data;
+    /// a value that is not listed has none of the attributes.
+    pub fn nominal(values: Vec<V>) -> Self {
+        let names = values.iter().map(|v| format!("={v}")).collect();
+        Self::new(names, move |x, k| *x == values[k])
+    }
+
+    /// Creates the dichotomic scale on the values `yes` and `no`, with
+    /// attributes `=yes` and `=no`.
+    ///
+    /// This is the nominal scale on two values; it suits yes/no attributes,
+    /// where both the presence and the absence of a property are of interest.
+    pub fn dichotomic(yes: V, no: V) -> Self {
+        Self::nominal(vec![yes, no])
+    }
+}
+
+impl<V: Display + PartialOrd + Send + Sync + 'static> Scale<V> {
+    /// Creates the ordinal scale with the given thresholds.
+    ///
+    /// There is one attribute `<=t` per threshold `t`, which a value has when
+    /// it is at most `t`.
+    pub fn ordinal(thresholds: Vec<V>) -> Self {
+        Self::biordinal(thresholds, Vec::new())
+    }
+
+    /// Creates the interordinal scale with the given thresholds.
+    ///
+    /// There are attributes `<=t` and `>=t` for each threshold `t`, so that
+    /// the intents of the scale describe intervals of values.
+    pub fn interordinal(thresholds: Vec<V>) -> Self
+    where
+        V: Clone,
+    {
+        Self::biordinal(thresholds.clone(), thresholds)
+    }
+
+    /// Creates the biordinal scale with lower thresholds `lower` and upper
+    /// thresholds `upper`.
+    ///
+    /// There is an attribute `<=t` for every lower threshold and `>=t` for
+    /// every upper threshold. This suits scales like ratings, where values
+    /// are either "at most this bad" or "at least this good".
+    pub fn biordinal(lower: Vec<V>, upper: Vec<V>) -> Self {
+        let names = lower
+            .iter()
+            .map(|t| format!("<={t}"))
+            .chain(upper.iter().map(|t| format!(">={t}")))
+            .collect();
+        Self::new(names, move |x, k| match lower.get(k) {
+            Some(t) => x <= t,
+            None => *x >= upper[k - lower.len()],
+        })
+    }
+}
+
+impl<V> Clone for Scale<V> {
+    fn clone(&self) -> Self {
+        Self {
+            attributes: self.attributes.clone(),
+            intent: Arc::clone(&self.intent),
+        }
+    }
+}
+
+impl<V> std::fmt::Debug for Scale<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Scale")
+            .field("attributes", &self.attributes)
+            .finish_non_exhaustive()
+    }
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(context: &FormalContext<String, String>, i: usize) -> Vec<&str> {
+        context
+            .get_object_intent(i)
+            .iter_ones()
+            .map(|j| context.attributes[j].as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_scales() {
+        let ordinal = Scale::ordinal(vec![1, 2, 3]);
+        assert_eq!(ordinal.attributes(), ["<=1", "<=2", "<=3"]);
+        assert_eq!(ordinal.intent(&2), Some(bitvec![0, 1, 1]));
+        assert_eq!(ordinal.intent(&0), Some(bitvec![1, 1, 1]));
+        assert_eq!(ordinal.intent(&4), Some(bitvec![0, 0, 0]));
+
+        let interordinal = Scale::interordinal(vec![1, 2, 3]);
+        assert_eq!(interordinal.intent(&2), Some(bitvec![0, 1, 1, 1, 1, 0]));
+
+        let biordinal = Scale::biordinal(vec![1.0, 2.0], vec![3.0, 4.0]);
+        assert_eq!(biordinal.attributes(), ["<=1", "<=2", ">=3", ">=4"]);
+        assert_eq!(biordinal.intent(&1.5), Some(bitvec![0, 1, 0, 0]));
+        assert_eq!(biordinal.intent(&4.5), Some(bitvec![0, 0, 1, 1]));
+
+        let nominal = Scale::nominal(vec!["red", "blue"]);
+        assert_eq!(nominal.attributes(), ["=red", "=blue"]);
+        assert_eq!(nominal.intent(&"blue"), Some(bitvec![0, 1]));
+        assert_eq!(nominal.intent(&"green"), Some(bitvec![0, 0]));
+
+        let dichotomic = Scale::dichotomic("yes", "no");
+        assert_eq!(dichotomic.attributes(), ["=yes", "=no"]);
+        assert_eq!(dichotomic.intent(&"no"), Some(bitvec![0, 1]));
+    }
+
+    #[test]
+    fn test_plain_scaling() {
+        let context = ManyValuedContext::new(
+            vec!["ann".into(), "bob".into(), "cid".into()],
+            vec!["age".into(), "color".into()],
+            vec![
+                vec![Some(Value::Age(27)), Some(Value::Color("red"))],
+                vec![Some(Value::Age(41)), None],
+                vec![None, Some(Value::Color("blue"))],
+            ],
+        );
+        let ages = Scale::ordinal(vec![Value::Age(30), Value::Age(50)]);
+        let colors = Scale::nominal(context.attribute_values(1));
+
+        let derived = context.scale(&[ages, colors]).unwrap();
+
+        assert_eq!(
+            derived.attributes,
+            ["age<=30", "age<=50", "color=red", "color=blue"]
+        );
+        assert_eq!(derived.objects, context.objects);
+        assert_eq!(labels(&derived, 0), ["age<=30", "age<=50", "color=red"]);
+        assert_eq!(labels(&derived, 1), ["age<=50"]);
+        assert_eq!(labels(&derived, 2), ["color=blue"]);
+        assert!(derived.validate());
+    }
+
+    // A mixed column type; only ages are ordered.
+    #[derive(Debug, Clone, PartialEq, PartialOrd)]
+    enum Value {
+        Age(u32),
+        Color(&'static str),
+    }
+
+    impl Display for Value {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Value::Age(age) => write!(f, "{age}"),
+                Value::Color(color) => write!(f, "{color}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_nominal_scale_needs_only_equality() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Color(&'static str);
+
+        impl Display for Color {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.0)
+            }
+        }
+
+        let context = ManyValuedContext::new(
+            vec!["x".into(), "y".into()],
+            vec!["color".into()],
+            vec![vec![Some(Color("red"))], vec![Some(Color("blue"))]],
+        );
+        let colors = Scale::nominal(context.attribute_values(0));
+
+        let derived = context.scale(&[colors]).unwrap();
+
+        assert_eq!(labels(&derived, 1), ["color=blue"]);
+    }
+
+    #[test]
+    fn test_custom_scale() {
+        let context = ManyValuedContext::new(
+            vec!["x".into(), "y".into()],
+            vec!["size".into(), "parity".into()],
+            vec![vec![Some(1), Some(1)], vec![Some(3), Some(4)]],
+        );
+        let contranomial = FormalContext::contranomial_scale(vec![1, 2, 3]);
+        let sizes = Scale::from_context(FormalContext::new(
+            contranomial.objects.clone(),
+            contranomial
+                .attributes
+                .iter()
+                .map(|m| format!("!={m}"))
+                .collect(),
+            (0..3)
+                .map(|i| contranomial.get_object_intent(i).clone())
+                .collect(),
+        ));
+        let parity = Scale::new(vec![" even".into()], |x: &i32, _| x % 2 == 0);
+
+        let derived = context.scale(&[sizes, parity]).unwrap();
+
+        assert_eq!(labels(&derived, 0), ["size!=2", "size!=3"]);
+        assert_eq!(labels(&derived, 1), ["size!=1", "size!=2", "parity even"]);
+    }
+
+    #[test]
+    fn test_scale_reports_unplaceable_values() {
+        let context = ManyValuedContext::new(
+            vec!["x".into(), "y".into()],
+            vec!["n".into()],
+            vec![vec![Some(1)], vec![Some(5)]],
+        );
+        let scale = Scale::from_context(FormalContext::new(
+            vec![1, 2],
+            vec!["=1".into()],
+            vec![bitvec![1], bitvec![0]],
+        ));
+
+        let err = context.scale(&[scale]).unwrap_err();
+
+        assert_eq!(
+            err,
+            ScaleError {
+                object: 1,
+                attribute: 0
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "value of attribute 0 for object 1 is not in its scale"
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use crate::FormalContext;
+use crate::RawFormalConcept;
+use crate::bit_fiddling::*;
+
+use bitvec::prelude::*;
+
+// Ganter's NextClosure walks the intents of a context one at a time, in lectic
+// order: intent `X` comes before intent `Y` when the first attribute (by index)
+// on which they differ belongs to `Y`. The only state needed to continue is
+// the current intent, which makes the enumeration sequential, deterministic
+// and resumable. It shares the `u128` engines with PCbO, so each step is a
+// handful of closures on masks or word slices.
+
+// Iterator state for both engines. `next` is the intent to be yielded next, or
+// `None` once the last intent (the full attribute set) has been yielded.
+enum LecticIter {
+    Mask {
+        context: MaskContext,
+        next: Option<u128>,
+    },
+    Dense {
+        context: DenseContext,
+        next: Option<Vec<u128>>,
+    },
+}
+
+impl<A: Sync, B: Sync> FormalContext<A, B> {
+    /// Returns an iterator over all raw formal concepts, ordered lectically by
+    /// intent.
+    ///
+    /// Intent `X` precedes intent `Y` when the first attribute index on which
+    /// they differ belongs to `Y`. The first concept is therefore the maximal
+    /// concept, and the last one has every attribute in its intent. Unlike
+    /// [`FormalContext::all_concepts_raw`], the order is canonical and does
+    /// not depend on the number of threads. Concepts are computed lazily, one
+    /// at a time.
+    pub fn lectic_concepts_iter(&self) -> impl Iterator<Item = RawFormalConcept> {
+        if let Some(context) = self.mask_context() {
+            let next = Some(context.close_intent(0));
+            return LecticIter::Mask { context, next };
+        }
+
+        let context = self.dense_context();
+        let empty = vec![0; self.attributes.len().div_ceil(u128::BITS as usize)];
+        let next = Some(context.close_intent(&empty));
+        LecticIter::Dense { context, next }
+    }
+
+    /// Returns an iterator over the raw formal concepts whose intents come
+    /// strictly after `intent` in lectic order.
+    ///
+    /// This resumes [`FormalContext::lectic_concepts_iter`]: passing the
+    /// intent of the last concept seen continues the enumeration from the
+    /// following concept. `intent` does not need to be closed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `intent.len()` differs from the number of attributes.
+    pub fn lectic_concepts_iter_from(
+        &self,
+        intent: &BitVec,
+    ) -> impl Iterator<Item = RawFormalConcept> {
+        assert_eq!(intent.len(), self.attributes.len());
+        if let Some(context) = self.mask_context() {
+            let next = mask_next_closure(bitvec_to_mask(intent), self.attributes.len(), |set| {
+                context.close_intent(set)
+            });
+            return LecticIter::Mask { context, next };
+        }
+
+        let context = self.dense_context();
+        let next = dense_next_closure(
+            &context.intent_from_bitvec(intent),
+            self.attributes.len(),
+            |set| context.close_intent(set),
+        );
+        LecticIter::Dense { context, next }
+    }
+}
+
+impl Iterator for LecticIter {
+    type Item = RawFormalConcept;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            LecticIter::Mask { context, next } => {
+                let intent = (*next)?;
+                let concept = context.concept_of_intent(intent);
+                *next = mask_next_closure(intent, context.attributes_len(), |set| {
+                    context.close_intent(set)
+                });
+                Some(context.to_raw_concept(concept))
+            }
+            LecticIter::Dense { context, next } => {
+                let intent = next.take()?;
+                let concept = context.concept_of_intent(&intent);
+                *next = dense_next_closure(&intent, context.attributes_len(), |set| {
+                    context.close_intent(set)
+                });
+                Some(context.to_raw_concept(&concept))
+            }
+        }
+    }
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterned_context(objects_len: usize) -> FormalContext<usize, usize> {
+        let relation = (0..objects_len)
+            .map(|object| {
+                (0..10)
+                    .map(|attribute| (object * 7 + attribute * 3) % 5 < 3)
+                    .collect::<BitVec>()
+            })
+            .collect();
+        FormalContext::new((0..objects_len).collect(), (0..10).collect(), relation)
+    }
+
+    fn assert_lectic(context: &FormalContext<usize, usize>) {
+        let mut expected: Vec<BitVec> = context
+            .all_concepts_raw()
+            .into_iter()
+            .map(|concept| concept.intent)
+            .collect();
+        expected.sort_by(lectic_cmp);
+
+        let concepts: Vec<RawFormalConcept> = context.lectic_concepts_iter().collect();
+        let intents: Vec<BitVec> = concepts.iter().map(|c| c.intent.clone()).collect();
+
+        assert_eq!(intents, expected);
+        assert!(
+            concepts
+                .iter()
+                .all(|c| c.extent == context.induce_l(&c.intent))
+        );
+    }
+
+    #[test]
+    fn test_lectic_order_masked() {
+        assert_lectic(&patterned_context(12));
+    }
+
+    #[test]
+    fn test_lectic_order_dense() {
+        assert_lectic(&patterned_context(130));
+    }
+
+    #[test]
+    fn test_lectic_resume() {
+        let context = patterned_context(12);
+        let all: Vec<RawFormalConcept> = context.lectic_concepts_iter().collect();
+
+        for i in 0..all.len() {
+            let rest: Vec<RawFormalConcept> =
+                context.lectic_concepts_iter_from(&all[i].intent).collect();
+            assert_eq!(rest, all[i + 1..]);
+        }
+    }
+}
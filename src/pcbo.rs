@@ -35,43 +35,52 @@ use std::sync::Arc;
 // but handy while profiling real datasets.
 const PARALLEL_FRONTIER_FACTOR: usize = 8;
 
-// One frame in the explicit DFS iterator used by the masked parallel iterator.
-// `next_j` is the next candidate attribute to try for this frame.
-// `yielded` records whether the frame's own concept has already been emitted.
-struct MaskFrame {
-    concept: MaskConcept,
-    next_j: usize,
-    yielded: bool,
-}
+// The search state of Close-by-One over some representation of closed sets.
+//
+// `MaskContext` and `DenseContext` search the intents of a formal context in
+// their own bitset types, and `ClosureSearch` (in `closure_operator.rs`)
+// searches the closed sets of any `ClosureOperator`. The traversal below,
+// sequential or parallel, is shared by all of them.
+pub(crate) trait CbOSearch: Sync {
+    type Node: Send;
+
+    // The number of elements closed sets are drawn from.
+    fn elements_len(&self) -> usize;
 
-// Iterator over one masked PCbO subtree. Rayon receives one of these per
-// frontier subtree, so results can stream out without first allocating a giant
-// `Vec<MaskConcept>` inside each worker.
-struct MaskRawSubtreeIter {
-    context: Arc<MaskContext>,
-    stack: Vec<MaskFrame>,
+    // The smallest closed set, which is the root of the search tree.
+    fn root(&self) -> Self::Node;
+
+    fn node_contains(&self, node: &Self::Node, element: usize) -> bool;
+
+    // The closure of `node` plus `element`, or `None` when it fails the
+    // canonicity test (or is pruned for another reason, such as support).
+    fn canonical_child(&self, node: &Self::Node, element: usize) -> Option<Self::Node>;
 }
 
-// One frame in the explicit DFS iterator used by the dense parallel iterator.
-// This is the multi-word analogue of `MaskFrame`.
-struct DenseFrame {
-    concept: DenseConcept,
+// One frame in the explicit DFS iterator used by `SubtreeIter`.
+// `next_j` is the next candidate element to try for this frame.
+// `yielded` records whether the frame's own node has already been emitted.
+struct Frame<N> {
+    node: N,
     next_j: usize,
     yielded: bool,
 }
 
-// Iterator over one dense PCbO subtree. This has the same job as
-// `MaskRawSubtreeIter`, but each concept stores multiple `u128` words.
-struct DenseRawSubtreeIter {
-    context: Arc<DenseContext>,
-    stack: Vec<DenseFrame>,
+// Iterator over one CbO subtree, yielding `output(node)` for every node. Rayon
+// receives one of these per frontier subtree, so results can stream out
+// without first allocating a giant `Vec` of nodes inside each worker. Rooted at
+// the root node, it is also the sequential iterator over every closed set.
+pub(crate) struct SubtreeIter<S: CbOSearch, F> {
+    search: Arc<S>,
+    stack: Vec<Frame<S::Node>>,
+    output: F,
 }
 
 impl<A: Sync, B: Sync> FormalContext<A, B> {
     // Build the `u128` fast-path context when the matrix fits in one mask per
     // extent/intent. Returning `None` sends larger contexts to the dense
     // multi-word implementation.
-    fn mask_context(&self) -> Option<MaskContext> {
+    pub(crate) fn mask_context(&self) -> Option<MaskContext> {
         MaskContext::from_bitvecs(
             self.objects.len(),
             self.attributes.len(),
@@ -82,7 +91,7 @@ impl<A: Sync, B: Sync> FormalContext<A, B> {
 
     // Build the arbitrary-size dense engine. This is used when the scalar
     // `u128` path cannot represent a whole extent/intent in one register.
-    fn dense_context(&self) -> DenseContext {
+    pub(crate) fn dense_context(&self) -> DenseContext {
         DenseContext::from_bitvecs(
             self.objects.len(),
             self.attributes.len(),
@@ -97,67 +106,14 @@ impl<A: Sync, B: Sync> FormalContext<A, B> {
     // best API for enormous lattices like S_5: materializing hundreds of
     // millions of heap-backed `BitVec` pairs is inherently expensive.
     fn all_concepts_raw_masked(&self, context: &MaskContext) -> Vec<RawFormalConcept> {
-        let (prefix, frontier) = context.parallel_frontier();
-        let mut concepts = prefix
-            .into_iter()
-            .map(|concept| context.to_raw_concept(concept))
-            .collect::<Vec<_>>();
-        let mut subtrees = frontier
-            .into_par_iter()
-            .flat_map_iter(|(concept, y)| {
-                let mut concepts = Vec::new();
-                context.collect_subtree(concept, y, &mut concepts);
-                concepts
-                    .into_iter()
-                    .map(|concept| context.to_raw_concept(concept))
-            })
-            .collect();
-        concepts.append(&mut subtrees);
-        concepts
-    }
-
-    // Count concepts using the masked fast path, avoiding output conversion.
-    fn num_concepts_masked(&self, context: &MaskContext) -> usize {
-        let (prefix, frontier) = context.parallel_frontier();
-        prefix.len()
-            + frontier
-                .into_par_iter()
-                .map(|(concept, y)| context.count_subtree(concept, y))
-                .sum::<usize>()
+        collect_all(context, |concept| context.to_raw_concept(concept))
     }
 
     // Materialize all dense concepts into public `RawFormalConcept`s. Like the
     // masked materialization path, this is useful for moderate result sets but
     // unsuitable for lattices with hundreds of millions of concepts.
     fn all_concepts_raw_dense(&self, context: &DenseContext) -> Vec<RawFormalConcept> {
-        let (prefix, frontier) = context.parallel_frontier();
-        let mut concepts = prefix
-            .into_iter()
-            .map(|concept| context.to_raw_concept(&concept))
-            .collect::<Vec<_>>();
-        let mut subtrees = frontier
-            .into_par_iter()
-            .flat_map_iter(|(concept, y)| {
-                let mut concepts = Vec::new();
-                context.collect_subtree(concept, y, &mut concepts);
-                concepts
-                    .into_iter()
-                    .map(|concept| context.to_raw_concept(&concept))
-            })
-            .collect();
-        concepts.append(&mut subtrees);
-        concepts
-    }
-
-    // Count concepts using the dense multi-word path, avoiding output
-    // conversion. This is the large-context analogue of `num_concepts_masked`.
-    fn num_concepts_dense(&self, context: &DenseContext) -> usize {
-        let (prefix, frontier) = context.parallel_frontier();
-        prefix.len()
-            + frontier
-                .into_par_iter()
-                .map(|(concept, y)| context.count_subtree(concept, y))
-                .sum::<usize>()
+        collect_all(context, |concept| context.to_raw_concept(&concept))
     }
 
     /// Returns a parallel iterator over all raw formal concepts.
@@ -174,14 +130,20 @@ impl<A: Sync, B: Sync> FormalContext<A, B> {
             // The masked branch stores the precomputed context in an `Arc`
             // because each Rayon worker owns an iterator over one subtree.
             let context = Arc::new(context);
-            let (prefix, frontier) = context.parallel_frontier();
+            let (prefix, frontier) = parallel_frontier(&*context);
             let prefix_context = context.clone();
             return Either::Left(
                 prefix
                     .into_par_iter()
                     .map(move |concept| prefix_context.to_raw_concept(concept))
                     .chain(frontier.into_par_iter().flat_map_iter(move |(concept, y)| {
-                        MaskRawSubtreeIter::new(context.clone(), concept, y)
+                        let output = context.clone();
+                        SubtreeIter::new(
+                            context.clone(),
+                            concept,
+                            y,
+                            move |concept: &MaskConcept| output.to_raw_concept(*concept),
+                        )
                     })),
             );
         }
@@ -189,24 +151,82 @@ impl<A: Sync, B: Sync> FormalContext<A, B> {
         // Larger contexts use the same PCbO structure, but with explicit
         // `u128` word slices instead of general-purpose `BitVec` operations.
         let context = Arc::new(self.dense_context());
-        let (prefix, frontier) = context.parallel_frontier();
+        let (prefix, frontier) = parallel_frontier(&*context);
         let prefix_context = context.clone();
         Either::Right(
             prefix
                 .into_par_iter()
                 .map(move |concept| prefix_context.to_raw_concept(&concept))
                 .chain(frontier.into_par_iter().flat_map_iter(move |(concept, y)| {
-                    DenseRawSubtreeIter::new(context.clone(), concept, y)
+                    let output = context.clone();
+                    SubtreeIter::new(
+                        context.clone(),
+                        concept,
+                        y,
+                        move |concept: &DenseConcept| output.to_raw_concept(concept),
+                    )
                 })),
         )
     }
 
+    /// Returns a sequential iterator over all raw formal concepts.
+    ///
+    /// Concepts are generated lazily by a depth-first PCbO traversal on the
+    /// calling thread, without starting a Rayon thread pool. Only the current
+    /// search path is kept in memory, so consumers can `take`, `find`, or stop
+    /// early without paying for the rest of the lattice. Concept order is an
+    /// implementation detail and should not be relied on.
+    pub fn all_concepts_raw_iter(&self) -> impl Iterator<Item = RawFormalConcept> {
+        self.all_concepts_raw_iter_with_min_support(0)
+    }
+
+    /// Returns a sequential iterator over the raw formal concepts whose extent
+    /// contains at least `min_support` objects.
+    ///
+    /// This is the lazy counterpart of
+    /// [`FormalContext::all_concepts_raw_with_min_support`]: the search is
+    /// pruned below the threshold as there, and concepts are generated on
+    /// the calling thread as by [`FormalContext::all_concepts_raw_iter`].
+    pub fn all_concepts_raw_iter_with_min_support(
+        &self,
+        min_support: usize,
+    ) -> impl Iterator<Item = RawFormalConcept> {
+        // Every extent is contained in the maximal one, so nothing qualifies.
+        let iter = (self.objects.len() >= min_support).then(|| {
+            if let Some(context) = self.mask_context() {
+                // A single subtree rooted at the maximal concept with `y = 0`
+                // covers the whole PCbO search tree.
+                let context = Arc::new(context.with_min_support(min_support));
+                let root = context.max_concept();
+                let output = context.clone();
+                return Either::Left(SubtreeIter::new(
+                    context,
+                    root,
+                    0,
+                    move |concept: &MaskConcept| output.to_raw_concept(*concept),
+                ));
+            }
+
+            let context = Arc::new(self.dense_context().with_min_support(min_support));
+            let root = context.max_concept();
+            let output = context.clone();
+            Either::Right(SubtreeIter::new(
+                context,
+                root,
+                0,
+                move |concept: &DenseConcept| output.to_raw_concept(concept),
+            ))
+        });
+        iter.into_iter().flatten()
+    }
+
     /// Enumerates all raw formal concepts in this context.
     ///
     /// Raw concepts store only extent and intent bitsets, so this avoids the
     /// per-concept `Arc<FormalContext<_, _>>` wrapping used by
     /// [`FormalConcept`]. Concept order is an implementation detail and should
-    /// not be relied on.
+    /// not be relied on; use [`FormalContext::lectic_concepts_iter`] when a
+    /// canonical order is needed.
     pub fn all_concepts_raw(&self) -> Vec<RawFormalConcept> {
         // If the context fits the fast path, keep the inner traversal in masks
         // and convert once, at the API boundary.
@@ -225,32 +245,96 @@ impl<A: Sync, B: Sync> FormalContext<A, B> {
         // Counting is the most efficient way to benchmark or size very large
         // lattices because it avoids allocating a `RawFormalConcept` per result.
         if let Some(context) = self.mask_context() {
-            return self.num_concepts_masked(&context);
+            return count_all(&context);
+        }
+
+        count_all(&self.dense_context())
+    }
+
+    /// Enumerates the raw formal concepts whose extent contains at least
+    /// `min_support` objects.
+    ///
+    /// In data-mining terms, the intents of these concepts are the frequent
+    /// closed itemsets of the context, and the concepts form its iceberg
+    /// lattice. The PCbO search is pruned as soon as an extent falls below the
+    /// threshold, so infrequent parts of the lattice are never explored.
+    /// Concept order is an implementation detail and should not be relied on.
+    pub fn all_concepts_raw_with_min_support(&self, min_support: usize) -> Vec<RawFormalConcept> {
+        // Every extent is contained in the maximal one, so nothing qualifies.
+        if self.objects.len() < min_support {
+            return Vec::new();
+        }
+
+        if let Some(context) = self.mask_context() {
+            return self.all_concepts_raw_masked(&context.with_min_support(min_support));
+        }
+
+        self.all_concepts_raw_dense(&self.dense_context().with_min_support(min_support))
+    }
+
+    /// Counts the formal concepts whose extent contains at least `min_support`
+    /// objects.
+    ///
+    /// See [`FormalContext::all_concepts_raw_with_min_support`] for details.
+    pub fn num_concepts_with_min_support(&self, min_support: usize) -> usize {
+        if self.objects.len() < min_support {
+            return 0;
+        }
+
+        if let Some(context) = self.mask_context() {
+            return count_all(&context.with_min_support(min_support));
         }
 
-        self.num_concepts_dense(&self.dense_context())
+        count_all(&self.dense_context().with_min_support(min_support))
+    }
+
+    /// Converts a relative support, the fraction `min_support` of all
+    /// objects, into the smallest number of objects that reaches it.
+    ///
+    /// This is `ceil(min_support * objects.len())`, except that products
+    /// within rounding error of an integer are taken to be that integer, so
+    /// that `0.07` of 100 objects is 7 rather than 8.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_support` is not between 0 and 1.
+    pub fn min_support_count(&self, min_support: f64) -> usize {
+        assert!(
+            (0.0..=1.0).contains(&min_support),
+            "Relative support must be between 0 and 1"
+        );
+        let threshold = min_support * self.objects.len() as f64;
+        let nearest = threshold.round();
+        // `min_support` has a relative error of at most one half ulp, and
+        // the product adds another.
+        if (threshold - nearest).abs() <= 4.0 * f64::EPSILON * threshold {
+            nearest as usize
+        } else {
+            threshold.ceil() as usize
+        }
     }
 }
 
-impl MaskRawSubtreeIter {
+impl<S: CbOSearch, F> SubtreeIter<S, F> {
     // Start a depth-first walk at one frontier root. The iterator yields the
-    // root first, then explores children in increasing attribute order.
-    fn new(context: Arc<MaskContext>, concept: MaskConcept, y: usize) -> Self {
+    // root first, then explores children in increasing element order.
+    pub(crate) fn new(search: Arc<S>, node: S::Node, y: usize, output: F) -> Self {
         Self {
-            context,
-            stack: vec![MaskFrame {
-                concept,
+            search,
+            stack: vec![Frame {
+                node,
                 next_j: y,
                 yielded: false,
             }],
+            output,
         }
     }
 }
 
-impl Iterator for MaskRawSubtreeIter {
-    type Item = RawFormalConcept;
+impl<S: CbOSearch, T, F: FnMut(&S::Node) -> T> Iterator for SubtreeIter<S, F> {
+    type Item = T;
 
-    fn next(&mut self) -> Option<Self::Item> {
+    fn next(&mut self) -> Option<T> {
         loop {
             // `child` is held outside the borrow of `self.stack.last_mut()` so
             // we can push onto the stack after the mutable borrow of the top
@@ -260,40 +344,40 @@ impl Iterator for MaskRawSubtreeIter {
             {
                 let frame = self.stack.last_mut()?;
                 if !frame.yielded {
-                    // Emit each frame's concept exactly once. The iterator is
+                    // Emit each frame's node exactly once. The iterator is
                     // pre-order; order is not semantically important for the
                     // public parallel iterator, but pre-order keeps the state
                     // machine simple.
                     frame.yielded = true;
-                    return Some(self.context.to_raw_concept(frame.concept));
+                    return Some((self.output)(&frame.node));
                 }
 
-                // Resume scanning candidate attributes where this frame left
+                // Resume scanning candidate elements where this frame left
                 // off. This is the explicit-stack equivalent of a recursive
-                // `for j in y..attributes_len` loop.
-                while frame.next_j < self.context.attributes_len() {
+                // `for j in y..elements_len` loop.
+                while frame.next_j < self.search.elements_len() {
                     let j = frame.next_j;
                     frame.next_j += 1;
 
-                    if self.context.concept_has_attribute(frame.concept, j) {
+                    if self.search.node_contains(&frame.node, j) {
                         continue;
                     }
 
-                    // `child` performs the extent intersection, closure, and
-                    // canonicity check. `None` means the closure belongs to an
-                    // earlier branch.
-                    if let Some(concept) = self.context.child(frame.concept, j) {
-                        child = Some((concept, j + 1));
+                    // `canonical_child` performs the closure and canonicity
+                    // check. `None` means the closure belongs to an earlier
+                    // branch.
+                    if let Some(node) = self.search.canonical_child(&frame.node, j) {
+                        child = Some((node, j + 1));
                         break;
                     }
                 }
             }
 
-            if let Some((concept, y)) = child {
+            if let Some((node, y)) = child {
                 // Descend to the next child. It inherits the canonical lower
                 // bound `j + 1`, stored here as `y`.
-                self.stack.push(MaskFrame {
-                    concept,
+                self.stack.push(Frame {
+                    node,
                     next_j: y,
                     yielded: false,
                 });
@@ -305,224 +389,149 @@ impl Iterator for MaskRawSubtreeIter {
     }
 }
 
-impl MaskContext {
-    // Generate scalar-mask children for frontier construction. Returning a
-    // small `Vec` here is fine because this runs near the top of the tree, not
-    // once per generated concept.
-    fn children(&self, concept: MaskConcept, y: usize) -> Vec<(MaskConcept, usize)> {
-        let mut result = Vec::new();
-        for j in y..self.attributes_len() {
-            if self.concept_has_attribute(concept, j) {
-                continue;
-            }
+// Generate children for frontier construction. Returning a small `Vec` here is
+// fine because this runs near the top of the tree, not once per generated node.
+fn children<S: CbOSearch>(search: &S, node: &S::Node, y: usize) -> Vec<(S::Node, usize)> {
+    let mut result = Vec::new();
+    for j in y..search.elements_len() {
+        if search.node_contains(node, j) {
+            continue;
+        }
 
-            if let Some(child) = self.child(concept, j) {
-                result.push((child, j + 1));
-            }
+        if let Some(child) = search.canonical_child(node, j) {
+            result.push((child, j + 1));
         }
-        result
     }
+    result
+}
 
-    // Recursive masked collection for APIs that materialize a `Vec`. The
-    // streaming parallel iterator uses `MaskRawSubtreeIter` instead, which
-    // avoids temporary subtree vectors for large enumeration.
-    fn collect_subtree(&self, concept: MaskConcept, y: usize, concepts: &mut Vec<MaskConcept>) {
-        for j in y..self.attributes_len() {
-            if self.concept_has_attribute(concept, j) {
-                continue;
-            }
+// Recursive collection for APIs that materialize a `Vec`. The streaming
+// parallel iterator uses `SubtreeIter` instead, which avoids temporary subtree
+// vectors for large enumeration.
+fn collect_subtree<S: CbOSearch>(search: &S, node: S::Node, y: usize, nodes: &mut Vec<S::Node>) {
+    for j in y..search.elements_len() {
+        if search.node_contains(&node, j) {
+            continue;
+        }
 
-            if let Some(child) = self.child(concept, j) {
-                self.collect_subtree(child, j + 1, concepts);
-            }
+        if let Some(child) = search.canonical_child(&node, j) {
+            collect_subtree(search, child, j + 1, nodes);
         }
-        concepts.push(concept);
     }
+    nodes.push(node);
+}
 
-    // Recursive masked count. This is the fastest path for huge lattices when
-    // the caller only needs cardinality, because no public `BitVec`s are
-    // allocated for individual concepts.
-    fn count_subtree(&self, concept: MaskConcept, y: usize) -> usize {
-        let mut count = 1;
-        for j in y..self.attributes_len() {
-            if self.concept_has_attribute(concept, j) {
-                continue;
-            }
+// Recursive count. This is the fastest path for huge lattices when the caller
+// only needs cardinality, because no public `BitVec`s are allocated for
+// individual nodes.
+fn count_subtree<S: CbOSearch>(search: &S, node: S::Node, y: usize) -> usize {
+    let mut count = 1;
+    for j in y..search.elements_len() {
+        if search.node_contains(&node, j) {
+            continue;
+        }
 
-            if let Some(child) = self.child(concept, j) {
-                count += self.count_subtree(child, j + 1);
-            }
+        if let Some(child) = search.canonical_child(&node, j) {
+            count += count_subtree(search, child, j + 1);
         }
-        count
     }
+    count
+}
 
-    // Build the same breadth-first Rayon frontier as the generic implementation
-    // but with masked concepts. The prefix/frontier split avoids fine-grained
-    // Rayon scheduling inside the deep PCbO recursion.
-    fn parallel_frontier(&self) -> (Vec<MaskConcept>, Vec<(MaskConcept, usize)>) {
-        let threads = rayon::current_num_threads();
-        let target_frontier = if threads <= 1 {
-            1
-        } else {
-            threads * PARALLEL_FRONTIER_FACTOR
+// Subtrees still to be searched, as `(node, y)` pairs.
+type Frontier<S> = Vec<(<S as CbOSearch>::Node, usize)>;
+
+// Build a breadth-first Rayon frontier. The prefix/frontier split avoids
+// fine-grained Rayon scheduling inside the deep CbO recursion.
+pub(crate) fn parallel_frontier<S: CbOSearch>(search: &S) -> (Vec<S::Node>, Frontier<S>) {
+    let threads = rayon::current_num_threads();
+    let target_frontier = if threads <= 1 {
+        1
+    } else {
+        threads * PARALLEL_FRONTIER_FACTOR
+    };
+    let mut prefix = Vec::new();
+    let mut frontier = VecDeque::from([(search.root(), 0)]);
+
+    while frontier.len() < target_frontier {
+        let Some((node, y)) = frontier.pop_front() else {
+            break;
         };
-        let mut prefix = Vec::new();
-        let mut frontier = VecDeque::from([(self.max_concept(), 0)]);
-
-        while frontier.len() < target_frontier {
-            let Some((concept, y)) = frontier.pop_front() else {
-                break;
-            };
-
-            let children = self.children(concept, y);
-            prefix.push(concept);
-            frontier.extend(children);
-        }
 
-        (prefix, frontier.into())
+        let children = children(search, &node, y);
+        prefix.push(node);
+        frontier.extend(children);
     }
-}
 
-impl DenseRawSubtreeIter {
-    // Start a streaming DFS over a dense frontier subtree.
-    fn new(context: Arc<DenseContext>, concept: DenseConcept, y: usize) -> Self {
-        Self {
-            context,
-            stack: vec![DenseFrame {
-                concept,
-                next_j: y,
-                yielded: false,
-            }],
-        }
-    }
+    (prefix, frontier.into())
 }
 
-impl Iterator for DenseRawSubtreeIter {
-    type Item = RawFormalConcept;
+// Materialize every node of the search tree as `output(node)`, in parallel.
+pub(crate) fn collect_all<S: CbOSearch, T: Send>(
+    search: &S,
+    output: impl Fn(S::Node) -> T + Sync,
+) -> Vec<T> {
+    let (prefix, frontier) = parallel_frontier(search);
+    let mut nodes = prefix.into_iter().map(&output).collect::<Vec<_>>();
+    let mut subtrees = frontier
+        .into_par_iter()
+        .flat_map_iter(|(node, y)| {
+            let mut nodes = Vec::new();
+            collect_subtree(search, node, y, &mut nodes);
+            nodes.into_iter().map(&output)
+        })
+        .collect();
+    nodes.append(&mut subtrees);
+    nodes
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            // Same state-machine shape as `MaskRawSubtreeIter`: inspect the
-            // current frame, optionally discover one child, then push that child
-            // after the mutable borrow of the frame has ended.
-            let mut child = None;
+// Count the nodes of the search tree in parallel, avoiding output conversion.
+pub(crate) fn count_all<S: CbOSearch>(search: &S) -> usize {
+    let (prefix, frontier) = parallel_frontier(search);
+    prefix.len()
+        + frontier
+            .into_par_iter()
+            .map(|(node, y)| count_subtree(search, node, y))
+            .sum::<usize>()
+}
 
-            {
-                let frame = self.stack.last_mut()?;
-                if !frame.yielded {
-                    // Yield before exploring descendants. Parallel iteration
-                    // does not promise a stable global order, but each subtree
-                    // still emits every concept exactly once.
-                    frame.yielded = true;
-                    return Some(self.context.to_raw_concept(&frame.concept));
-                }
+impl CbOSearch for MaskContext {
+    type Node = MaskConcept;
 
-                // Continue scanning candidate attributes from where this frame
-                // previously stopped.
-                while frame.next_j < self.context.attributes_len() {
-                    let j = frame.next_j;
-                    frame.next_j += 1;
+    fn elements_len(&self) -> usize {
+        self.attributes_len()
+    }
 
-                    if self.context.concept_has_attribute(&frame.concept, j) {
-                        continue;
-                    }
+    fn root(&self) -> MaskConcept {
+        self.max_concept()
+    }
 
-                    // `child` performs both the extent intersection and
-                    // canonicity check. `None` means the closure belongs to an
-                    // earlier branch.
-                    if let Some(concept) = self.context.child(&frame.concept, j) {
-                        child = Some((concept, j + 1));
-                        break;
-                    }
-                }
-            }
+    fn node_contains(&self, concept: &MaskConcept, attribute: usize) -> bool {
+        self.concept_has_attribute(*concept, attribute)
+    }
 
-            if let Some((concept, y)) = child {
-                // Descend. `y` is always the candidate attribute plus one,
-                // preserving the PCbO canonical generation order.
-                self.stack.push(DenseFrame {
-                    concept,
-                    next_j: y,
-                    yielded: false,
-                });
-            } else {
-                // No candidates remain for the current frame.
-                self.stack.pop();
-            }
-        }
+    fn canonical_child(&self, concept: &MaskConcept, attribute: usize) -> Option<MaskConcept> {
+        self.child(*concept, attribute)
     }
 }
 
-impl DenseContext {
-    // Generate dense children for frontier construction. This is intentionally
-    // vector-returning because it runs only near the top of the tree.
-    fn children(&self, concept: &DenseConcept, y: usize) -> Vec<(DenseConcept, usize)> {
-        let mut result = Vec::new();
-        for j in y..self.attributes_len() {
-            if self.concept_has_attribute(concept, j) {
-                continue;
-            }
+impl CbOSearch for DenseContext {
+    type Node = DenseConcept;
 
-            if let Some(child) = self.child(concept, j) {
-                result.push((child, j + 1));
-            }
-        }
-        result
+    fn elements_len(&self) -> usize {
+        self.attributes_len()
     }
 
-    // Recursive materialization helper for `all_concepts_raw`. The public
-    // parallel iterator uses `DenseRawSubtreeIter` instead so it can stream.
-    fn collect_subtree(&self, concept: DenseConcept, y: usize, concepts: &mut Vec<DenseConcept>) {
-        for j in y..self.attributes_len() {
-            if self.concept_has_attribute(&concept, j) {
-                continue;
-            }
-
-            if let Some(child) = self.child(&concept, j) {
-                self.collect_subtree(child, j + 1, concepts);
-            }
-        }
-        concepts.push(concept);
+    fn root(&self) -> DenseConcept {
+        self.max_concept()
     }
 
-    // Recursive count helper. This avoids converting dense concepts back to
-    // `BitVec`, so it is the preferred path for measuring huge lattices.
-    fn count_subtree(&self, concept: DenseConcept, y: usize) -> usize {
-        let mut count = 1;
-        for j in y..self.attributes_len() {
-            if self.concept_has_attribute(&concept, j) {
-                continue;
-            }
-
-            if let Some(child) = self.child(&concept, j) {
-                count += self.count_subtree(child, j + 1);
-            }
-        }
-        count
+    fn node_contains(&self, concept: &DenseConcept, attribute: usize) -> bool {
+        self.concept_has_attribute(concept, attribute)
     }
 
-    // Build enough top-level dense subtrees for Rayon to balance work.
-    fn parallel_frontier(&self) -> (Vec<DenseConcept>, Vec<(DenseConcept, usize)>) {
-        let threads = rayon::current_num_threads();
-        let target_frontier = if threads <= 1 {
-            1
-        } else {
-            threads * PARALLEL_FRONTIER_FACTOR
-        };
-        let mut prefix = Vec::new();
-        let mut frontier = VecDeque::from([(self.max_concept(), 0)]);
-
-        while frontier.len() < target_frontier {
-            let Some((concept, y)) = frontier.pop_front() else {
-                break;
-            };
-
-            let children = self.children(&concept, y);
-            prefix.push(concept);
-            frontier.extend(children);
-        }
-
-        (prefix, frontier.into())
+    fn canonical_child(&self, concept: &DenseConcept, attribute: usize) -> Option<DenseConcept> {
+        self.child(concept, attribute)
     }
 }
 
@@ -540,6 +549,31 @@ impl<A: Clone + Send + Sync, B: Clone + Send + Sync> FormalContext<A, B> {
             })
     }
 
+    /// Returns a sequential iterator over all named formal concepts.
+    ///
+    /// This is the lazy way to enumerate concepts: they are generated one at
+    /// a time by a depth-first search on the calling thread, without starting
+    /// a Rayon thread pool, so `take`, `find`, or stopping after a time budget
+    /// only pay for the concepts actually visited. See
+    /// [`FormalContext::all_concepts_raw_iter`] for the raw variant. Each
+    /// yielded [`FormalConcept`] keeps an `Arc` to this context's labels.
+    /// Concept order is an implementation detail and should not be relied on.
+    pub fn concepts_iter(&self) -> impl Iterator<Item = FormalConcept<A, B>> {
+        let arc = self.arc();
+        self.all_concepts_raw_iter().map(move |data| FormalConcept {
+            context: arc.clone(),
+            data,
+        })
+    }
+
+    /// Returns a sequential iterator over all named formal concepts.
+    ///
+    /// Same as [`FormalContext::concepts_iter`], named after
+    /// [`FormalContext::all_concepts`].
+    pub fn all_concepts_iter(&self) -> impl Iterator<Item = FormalConcept<A, B>> {
+        self.concepts_iter()
+    }
+
     /// Enumerates all named formal concepts in this context.
     ///
     /// Each concept can iterate over the object and attribute labels in its
@@ -548,4 +582,32 @@ impl<A: Clone + Send + Sync, B: Clone + Send + Sync> FormalContext<A, B> {
     pub fn all_concepts(&self) -> Vec<FormalConcept<A, B>> {
         self.all_concepts_par_iter().collect()
     }
+
+    /// Enumerates the named formal concepts whose extent contains at least
+    /// `min_support` objects.
+    ///
+    /// See [`FormalContext::all_concepts_raw_with_min_support`] for details.
+    pub fn concepts_with_min_support(&self, min_support: usize) -> Vec<FormalConcept<A, B>> {
+        let arc = self.arc();
+        self.all_concepts_raw_with_min_support(min_support)
+            .into_iter()
+            .map(|data| FormalConcept {
+                context: arc.clone(),
+                data,
+            })
+            .collect()
+    }
+
+    /// Enumerates the named formal concepts whose extent contains at least the
+    /// fraction `min_support` of all objects.
+    ///
+    /// The absolute threshold is [`FormalContext::min_support_count`]; see
+    /// [`FormalContext::concepts_with_min_support`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_support` is not between 0 and 1.
+    pub fn concepts_with_min_relative_support(&self, min_support: f64) -> Vec<FormalConcept<A, B>> {
+        self.concepts_with_min_support(self.min_support_count(min_support))
+    }
 }
@@ -0,0 +1,206 @@
+use crate::FormalContext;
+use crate::bit_fiddling::*;
+
+use bitvec::prelude::*;
+use std::collections::HashMap;
+
+/// The objects and attributes merged by [`FormalContext::clarify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clarification<A, B> {
+    /// For each object of the clarified context, the labels of the original
+    /// objects with the same intent, starting with the one that was kept.
+    pub objects: Vec<Vec<A>>,
+    /// For each attribute of the clarified context, the labels of the
+    /// original attributes with the same extent, starting with the one that
+    /// was kept.
+    pub attributes: Vec<Vec<B>>,
+}
+
+/// The objects and attributes removed by
+/// [`FormalContext::reduce_with_mapping`].
+///
+/// Every removed object is listed with the indices, in the reduced context,
+/// of the objects whose intents intersect to its own intent: these are the
+/// remaining objects that have all of its attributes. Removed attributes are
+/// described in the same way by extents. A removed object belongs to the
+/// extent of a concept of the original context exactly when all of its listed
+/// objects belong to the corresponding extent of the reduced context, so
+/// concepts of the reduced context map back to the original one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reduction<A, B> {
+    /// The removed objects, each with the objects it is the intersection of.
+    pub objects: Vec<(A, Vec<usize>)>,
+    /// The removed attributes, each with the attributes it is the
+    /// intersection of.
+    pub attributes: Vec<(B, Vec<usize>)>,
+}
+
+impl<A: Clone, B: Clone> FormalContext<A, B> {
+    /// Clarifies this context in place, merging objects with identical
+    /// intents and attributes with identical extents.
+    ///
+    /// Of every group of identical rows or columns, the first is kept. The
+    /// returned [`Clarification`] lists, for each remaining object and
+    /// attribute, the labels of all rows or columns merged into it. The
+    /// concept lattice does not change.
+    pub fn clarify(&mut self) -> Clarification<A, B> {
+        let objects = merge_duplicates(&rows_of(self, true), &self.objects);
+        self.remove_objects_idx(&objects.removed);
+        let attributes = merge_duplicates(&rows_of(self, false), &self.attributes);
+        self.remove_attributes_idx(&attributes.removed);
+        Clarification {
+            objects: objects.merged,
+            attributes: attributes.merged,
+        }
+    }
+
+    /// Reduces this context in place like [`FormalContext::reduce`], and
+    /// returns which objects and attributes were removed, together with the
+    /// remaining ones they were intersections of; see [`Reduction`].
+    pub fn reduce_with_mapping(&mut self) -> Reduction<A, B> {
+        let original = self.clone();
+        let (objects, attributes) = self.reduce_tracked();
+        Reduction {
+            objects: removed_with_covers(&rows_of(&original, true), &objects, &original.objects),
+            attributes: removed_with_covers(
+                &rows_of(&original, false),
+                &attributes,
+                &original.attributes,
+            ),
+        }
+    }
+}
+
+// The object intents of `context` if `intents` holds, else its attribute
+// extents.
+fn rows_of<A, B>(context: &FormalContext<A, B>, intents: bool) -> Vec<BitVec> {
+    if intents {
+        (0..context.objects.len())
+            .map(|i| context.get_object_intent(i).clone())
+            .collect()
+    } else {
+        (0..context.attributes.len())
+            .map(|j| context.get_attribute_extent(j).clone())
+            .collect()
+    }
+}
+
+struct Merge<T> {
+    merged: Vec<Vec<T>>, // Labels merged into each kept row
+    removed: Vec<usize>, // Indices of the rows that are not kept
+}
+
+// Group the identical rows of `rows`, keeping the first of each group.
+fn merge_duplicates<T: Clone>(rows: &[BitVec], labels: &[T]) -> Merge<T> {
+    let mut first: HashMap<&BitVec, usize> = HashMap::new();
+    let mut merge = Merge {
+        merged: Vec::new(),
+        removed: Vec::new(),
+    };
+    for (i, row) in rows.iter().enumerate() {
+        match first.get(row) {
+            Some(&kept) => {
+                merge.merged[kept].push(labels[i].clone());
+                merge.removed.push(i);
+            }
+            None => {
+                first.insert(row, merge.merged.len());
+                merge.merged.push(vec![labels[i].clone()]);
+            }
+        }
+    }
+    merge
+}
+
+// Pair every row of `rows` that is not in `kept` with the positions in `kept`
+// of the kept rows containing it.
+fn removed_with_covers<T: Clone>(
+    rows: &[BitVec],
+    kept: &[usize],
+    labels: &[T],
+) -> Vec<(T, Vec<usize>)> {
+    (0..rows.len())
+        .filter(|i| !kept.contains(i))
+        .map(|i| {
+            let covers = kept
+                .iter()
+                .enumerate()
+                .filter(|&(_, &k)| is_subset(&rows[i], &rows[k]))
+                .map(|(position, _)| position)
+                .collect();
+            (labels[i].clone(), covers)
+        })
+        .collect()
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clarify() {
+        let mut context = FormalContext::new(
+            vec!["a", "b", "c", "d"],
+            vec!["1", "2", "3"],
+            vec![
+                bitvec![1, 0, 1],
+                bitvec![0, 1, 0],
+                bitvec![1, 0, 1],
+                bitvec![1, 0, 1],
+            ],
+        );
+        let num_concepts = context.num_concepts();
+
+        let clarification = context.clarify();
+
+        assert_eq!(clarification.objects, [vec!["a", "c", "d"], vec!["b"]]);
+        assert_eq!(clarification.attributes, [vec!["1", "3"], vec!["2"]]);
+        assert_eq!(context.objects, ["a", "b"]);
+        assert_eq!(context.attributes, ["1", "2"]);
+        assert_eq!(context.get_object_intent(0), &bitvec![1, 0]);
+        assert!(context.validate());
+        assert_eq!(context.num_concepts(), num_concepts);
+    }
+
+    #[test]
+    fn test_reduce_with_mapping() {
+        let original = FormalContext::new(
+            vec!["a", "b", "c", "d"],
+            vec!["1", "2", "3", "4"],
+            vec![
+                bitvec![1, 1, 0, 1],
+                bitvec![1, 0, 1, 1],
+                bitvec![1, 0, 0, 1], // a ∩ b
+                bitvec![1, 1, 0, 1], // same as a
+            ],
+        );
+        let mut context = original.clone();
+
+        let reduction = context.reduce_with_mapping();
+
+        let mut reduced = original.clone();
+        reduced.reduce();
+        assert_eq!(context, reduced);
+        assert_eq!(context.objects, ["b", "d"]);
+        assert_eq!(context.attributes, ["2", "3"]);
+        assert_eq!(reduction.objects, [("a", vec![1]), ("c", vec![0, 1])]);
+        // Attributes 1 and 4 are shared by every object: the intersection of
+        // no columns.
+        assert_eq!(reduction.attributes, [("1", vec![]), ("4", vec![])]);
+        for (label, covers) in &reduction.objects {
+            let i = original.objects.iter().position(|o| o == label).unwrap();
+            let meet = covers
+                .iter()
+                .map(|&p| {
+                    let k = original
+                        .objects
+                        .iter()
+                        .position(|o| *o == context.objects[p]);
+                    original.get_object_intent(k.unwrap()).clone()
+                })
+                .fold(BitVec::repeat(true, 4), |a, b| a & b);
+            assert_eq!(&meet, original.get_object_intent(i));
+        }
+    }
+}
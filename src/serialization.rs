@@ -0,0 +1,172 @@
+use crate::FormalConcept;
+use crate::FormalContext;
+use crate::RawFormalConcept;
+
+use bitvec::prelude::*;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+// Bitsets are written as strings of `0`s and `1`s, e.g. `"101"`, which keeps
+// their length and stays readable in JSON. A context is stored as its labels
+// and relation rows; the transposed relation is rebuilt by
+// `FormalContext::new` on deserialization rather than read from the input,
+// so the two can never disagree.
+
+impl<A: Serialize, B: Serialize> Serialize for FormalContext<A, B> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let relation: Vec<String> = (0..self.objects.len())
+            .map(|i| bits_to_string(self.get_object_intent(i)))
+            .collect();
+        let mut state = serializer.serialize_struct("FormalContext", 3)?;
+        state.serialize_field("objects", &self.objects)?;
+        state.serialize_field("attributes", &self.attributes)?;
+        state.serialize_field("relation", &relation)?;
+        state.end()
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename = "FormalContext")]
+struct ContextData<A, B> {
+    objects: Vec<A>,
+    attributes: Vec<B>,
+    relation: Vec<String>,
+}
+
+impl<'de, A: Deserialize<'de>, B: Deserialize<'de>> Deserialize<'de> for FormalContext<A, B> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = ContextData::deserialize(deserializer)?;
+        if data.relation.len() != data.objects.len() {
+            return Err(de::Error::custom(format!(
+                "relation has {} rows, but there are {} objects",
+                data.relation.len(),
+                data.objects.len()
+            )));
+        }
+        let relation = data
+            .relation
+            .iter()
+            .map(|row| {
+                let row = string_to_bits(row)?;
+                if row.len() != data.attributes.len() {
+                    return Err(de::Error::custom(format!(
+                        "relation row has {} entries, but there are {} attributes",
+                        row.len(),
+                        data.attributes.len()
+                    )));
+                }
+                Ok(row)
+            })
+            .collect::<Result<_, D::Error>>()?;
+        Ok(FormalContext::new(data.objects, data.attributes, relation))
+    }
+}
+
+impl Serialize for RawFormalConcept {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("RawFormalConcept", 2)?;
+        state.serialize_field("extent", &bits_to_string(&self.extent))?;
+        state.serialize_field("intent", &bits_to_string(&self.intent))?;
+        state.end()
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename = "RawFormalConcept")]
+struct RawConceptData {
+    extent: String,
+    intent: String,
+}
+
+impl<'de> Deserialize<'de> for RawFormalConcept {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = RawConceptData::deserialize(deserializer)?;
+        Ok(RawFormalConcept {
+            extent: string_to_bits(&data.extent)?,
+            intent: string_to_bits(&data.intent)?,
+        })
+    }
+}
+
+// A concept is written with the labels of its extent and intent; the context
+// is left out, since every concept of a lattice would otherwise repeat it.
+impl<A: Serialize, B: Serialize> Serialize for FormalConcept<A, B> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let extent: Vec<&A> = self.extent_names_iter().collect();
+        let intent: Vec<&B> = self.intent_names_iter().collect();
+        let mut state = serializer.serialize_struct("FormalConcept", 2)?;
+        state.serialize_field("extent", &extent)?;
+        state.serialize_field("intent", &intent)?;
+        state.end()
+    }
+}
+
+fn bits_to_string(bits: &BitSlice) -> String {
+    bits.iter()
+        .map(|bit| if *bit { '1' } else { '0' })
+        .collect()
+}
+
+fn string_to_bits<E: de::Error>(text: &str) -> Result<BitVec, E> {
+    text.chars()
+        .map(|ch| match ch {
+            '0' => Ok(false),
+            '1' => Ok(true),
+            _ => Err(E::custom(format!("invalid bit {ch:?} in {text:?}"))),
+        })
+        .collect()
+}
+
+// Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> FormalContext<String, String> {
+        FormalContext::new(
+            vec!["x".into(), "y".into()],
+            vec!["a".into(), "b".into(), "c".into()],
+            vec![bitvec![1, 0, 1], bitvec![0, 1, 1]],
+        )
+    }
+
+    #[test]
+    fn test_context_round_trips() {
+        let context = example();
+
+        let json = serde_json::to_string(&context).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"objects":["x","y"],"attributes":["a","b","c"],"relation":["101","011"]}"#
+        );
+        let parsed: FormalContext<String, String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, context);
+        assert!(parsed.validate());
+    }
+
+    #[test]
+    fn test_context_rejects_inconsistent_input() {
+        let parse = |json: &str| serde_json::from_str::<FormalContext<String, String>>(json);
+
+        assert!(parse(r#"{"objects":["x"],"attributes":["a"],"relation":[]}"#).is_err());
+        assert!(parse(r#"{"objects":["x"],"attributes":["a"],"relation":["10"]}"#).is_err());
+        assert!(parse(r#"{"objects":["x"],"attributes":["a"],"relation":["2"]}"#).is_err());
+    }
+
+    #[test]
+    fn test_concepts() {
+        let context = example();
+        let concept = context.max_concept();
+
+        assert_eq!(
+            serde_json::to_string(&concept).unwrap(),
+            r#"{"extent":["x","y"],"intent":["c"]}"#
+        );
+        let json = serde_json::to_string(&concept.data).unwrap();
+        assert_eq!(json, r#"{"extent":"11","intent":"001"}"#);
+        let raw: RawFormalConcept = serde_json::from_str(&json).unwrap();
+        assert_eq!(raw.extent, concept.data.extent);
+        assert_eq!(raw.intent, concept.data.intent);
+    }
+}
@@ -0,0 +1,43 @@
+// Fixtures shared by the unit tests of several modules.
+
+use crate::FormalContext;
+
+use bitvec::prelude::*;
+
+// The "lives in water" context from Ganter and Wille, whose lattice has 19
+// concepts.
+pub(crate) fn lives_in_water() -> FormalContext<&'static str, &'static str> {
+    FormalContext::new(
+        vec![
+            "fish leech",
+            "bream",
+            "frog",
+            "dog",
+            "water weeds",
+            "reed",
+            "bean",
+            "corn",
+        ],
+        vec![
+            "needs water to live",
+            "lives in water",
+            "lives on land",
+            "needs chlorophyll",
+            "dicotyledon",
+            "monocotyledon",
+            "can move",
+            "has limbs",
+            "breast feeds",
+        ],
+        vec![
+            bitvec![1, 1, 0, 0, 0, 0, 1, 0, 0], // fish leech
+            bitvec![1, 1, 0, 0, 0, 0, 1, 1, 0], // bream
+            bitvec![1, 1, 1, 0, 0, 0, 1, 1, 0], // frog
+            bitvec![1, 0, 1, 0, 0, 0, 1, 1, 1], // dog
+            bitvec![1, 1, 0, 1, 0, 1, 0, 0, 0], // water weeds
+            bitvec![1, 1, 1, 1, 0, 1, 0, 0, 0], // reed
+            bitvec![1, 0, 1, 1, 1, 0, 0, 0, 0], // bean
+            bitvec![1, 0, 1, 1, 0, 1, 0, 0, 0], // corn
+        ],
+    )
+}